@@ -90,7 +90,7 @@ impl AgentManifest {
 
 #[cfg(test)]
 mod tests {
-    use super::AgentManifest;
+    use super::{AgentManifest, Trigger};
 
     const LEGACY_MANIFEST: &str = r#"id: goni.agent.legacy
 version: 0.1.0
@@ -145,4 +145,5 @@ tools:
         assert_eq!(manifest.identity_requirements, vec!["user_session"]);
         assert!(manifest.remote_access);
     }
+
 }
@@ -0,0 +1,247 @@
+/// Language hint used to pick which syntactic boundaries a [`Chunker`] prefers to split on.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Language {
+    Rust,
+    Markdown,
+    PlainText,
+}
+
+/// One token-bounded slice of a source document, with enough provenance to cite `path:start-end`
+/// back to the caller.
+#[derive(Clone, Debug)]
+pub struct Chunk {
+    pub text: String,
+    pub source_path: String,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub token_count: usize,
+}
+
+/// Splits a document into chunks sized against a token budget.
+pub trait Chunker: Send + Sync {
+    fn chunk(&self, source_path: &str, language: Language, text: &str) -> Vec<Chunk>;
+}
+
+fn token_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+/// A line, tagged with whether it looks like a syntactic boundary (function/class/heading) for
+/// the given language.
+struct Line<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+    is_boundary: bool,
+}
+
+fn is_boundary_line(language: Language, trimmed: &str) -> bool {
+    match language {
+        Language::Rust => {
+            trimmed.starts_with("fn ")
+                || trimmed.starts_with("pub fn ")
+                || trimmed.starts_with("async fn ")
+                || trimmed.starts_with("pub async fn ")
+                || trimmed.starts_with("impl ")
+                || trimmed.starts_with("struct ")
+                || trimmed.starts_with("pub struct ")
+                || trimmed.starts_with("enum ")
+                || trimmed.starts_with("pub enum ")
+                || trimmed.starts_with("mod ")
+                || trimmed.starts_with("pub mod ")
+                || trimmed.starts_with("trait ")
+                || trimmed.starts_with("pub trait ")
+        }
+        Language::Markdown => trimmed.starts_with('#'),
+        Language::PlainText => trimmed.is_empty(),
+    }
+}
+
+fn split_lines(text: &str, language: Language) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    let mut pos = 0usize;
+    for raw in text.split_inclusive('\n') {
+        let trimmed_newline = raw.strip_suffix('\n').unwrap_or(raw);
+        let start = pos;
+        let end = start + trimmed_newline.len();
+        lines.push(Line {
+            text: trimmed_newline,
+            start,
+            end,
+            is_boundary: is_boundary_line(language, trimmed_newline.trim()),
+        });
+        pos += raw.len();
+    }
+    lines
+}
+
+/// Splits an over-long segment into groups of whole lines that each fit the budget. Lines are
+/// not split further: a single line longer than the budget becomes a group of its own.
+fn split_oversized<'a>(lines: &[Line<'a>], max_tokens: usize) -> Vec<Vec<&Line<'a>>> {
+    let mut groups: Vec<Vec<&Line<'a>>> = Vec::new();
+    let mut current: Vec<&Line<'a>> = Vec::new();
+    let mut current_tokens = 0usize;
+
+    for line in lines {
+        // Whole lines only; a blank line still counts as one token.
+        let sentence_tokens = token_count(line.text).max(1);
+        if current_tokens + sentence_tokens > max_tokens && !current.is_empty() {
+            groups.push(std::mem::take(&mut current));
+            current_tokens = 0;
+        }
+        current.push(line);
+        current_tokens += sentence_tokens;
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
+
+/// Heuristic chunker: prefers syntactic boundaries (function/class/heading/paragraph), falls
+/// back to line splits, and stitches a configurable token overlap between consecutive
+/// chunks so embeddings retain some cross-chunk context.
+///
+/// A chunk's own lines fit `max_tokens`, except that a single line longer than the budget is
+/// kept whole (lines are never split, not even on sentence boundaries). The overlap prefix is
+/// added on top, so a chunk's `token_count` can reach `max_tokens + overlap_tokens`.
+pub struct HeuristicChunker {
+    pub max_tokens: usize,
+    pub overlap_tokens: usize,
+}
+
+impl HeuristicChunker {
+    pub fn new(max_tokens: usize, overlap_tokens: usize) -> Self {
+        Self {
+            max_tokens,
+            overlap_tokens,
+        }
+    }
+}
+
+impl Chunker for HeuristicChunker {
+    fn chunk(&self, source_path: &str, language: Language, text: &str) -> Vec<Chunk> {
+        if text.is_empty() {
+            return Vec::new();
+        }
+
+        let lines = split_lines(text, language);
+
+        // Group lines into boundary-aligned segments: a new segment starts at every boundary
+        // line (or at the very start of the document).
+        let mut segments: Vec<Vec<&Line>> = Vec::new();
+        let mut current: Vec<&Line> = Vec::new();
+        for line in &lines {
+            if line.is_boundary && !current.is_empty() {
+                segments.push(std::mem::take(&mut current));
+            }
+            current.push(line);
+        }
+        if !current.is_empty() {
+            segments.push(current);
+        }
+
+        // Expand any segment that alone exceeds the budget into smaller sentence/line groups.
+        let mut groups: Vec<Vec<&Line>> = Vec::new();
+        for segment in segments {
+            let seg_tokens: usize = segment.iter().map(|l| token_count(l.text).max(1)).sum();
+            if seg_tokens > self.max_tokens {
+                groups.extend(split_oversized(&segment, self.max_tokens));
+            } else {
+                groups.push(segment);
+            }
+        }
+
+        // Pack groups into chunks, merging consecutive groups while they still fit the budget.
+        let mut chunks = Vec::new();
+        let mut pending: Vec<&Line> = Vec::new();
+        let mut pending_tokens = 0usize;
+
+        let mut flush = |pending: &mut Vec<&Line>, pending_tokens: &mut usize, chunks: &mut Vec<Chunk>| {
+            if pending.is_empty() {
+                return;
+            }
+            let start = pending.first().unwrap().start;
+            let end = pending.last().unwrap().end;
+            let body: String = pending.iter().map(|l| l.text).collect::<Vec<_>>().join("\n");
+
+            let overlap_prefix = chunks.last().map(|prev: &Chunk| {
+                let words: Vec<&str> = prev.text.split_whitespace().collect();
+                let tail_start = words.len().saturating_sub(self.overlap_tokens);
+                words[tail_start..].join(" ")
+            });
+
+            let full_text = match overlap_prefix {
+                Some(prefix) if !prefix.is_empty() => format!("{prefix} {body}"),
+                _ => body,
+            };
+
+            chunks.push(Chunk {
+                token_count: token_count(&full_text),
+                text: full_text,
+                source_path: source_path.to_string(),
+                start_byte: start,
+                end_byte: end,
+            });
+            pending.clear();
+            *pending_tokens = 0;
+        };
+
+        for group in groups {
+            let group_tokens: usize = group.iter().map(|l| token_count(l.text).max(1)).sum();
+            if pending_tokens + group_tokens > self.max_tokens && !pending.is_empty() {
+                flush(&mut pending, &mut pending_tokens, &mut chunks);
+            }
+            pending.extend(group);
+            pending_tokens += group_tokens;
+        }
+        flush(&mut pending, &mut pending_tokens, &mut chunks);
+
+        chunks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_rust_functions_that_fit_budget_into_one_chunk() {
+        let src = "fn a() {\n    1\n}\nfn b() {\n    2\n}\n";
+        let chunker = HeuristicChunker::new(100, 0);
+        let chunks = chunker.chunk("src/lib.rs", Language::Rust, src);
+        assert_eq!(chunks.len(), 1, "both functions fit in one chunk under budget");
+        assert!(chunks[0].text.contains("fn a"));
+        assert!(chunks[0].text.contains("fn b"));
+    }
+
+    #[test]
+    fn respects_token_budget() {
+        let src = "fn a() {\n    1\n}\nfn b() {\n    2\n}\n";
+        let chunker = HeuristicChunker::new(3, 0);
+        let chunks = chunker.chunk("src/lib.rs", Language::Rust, src);
+        assert!(chunks.len() >= 2, "tight budget should force a split");
+        for chunk in &chunks {
+            assert!(chunk.end_byte >= chunk.start_byte);
+        }
+    }
+
+    #[test]
+    fn overlap_duplicates_tail_tokens() {
+        let src = "fn a() {\n    one two three\n}\nfn b() {\n    four five six\n}\n";
+        let chunker = HeuristicChunker::new(6, 2);
+        let chunks = chunker.chunk("src/lib.rs", Language::Rust, src);
+        assert!(chunks.len() >= 2);
+        assert!(chunks[1].text.starts_with("three") || chunks[1].text.contains("three"));
+    }
+
+    #[test]
+    fn byte_offsets_are_in_source_order() {
+        let src = "# Title\npara one\n\n# Next\npara two\n";
+        let chunker = HeuristicChunker::new(2, 0);
+        let chunks = chunker.chunk("doc.md", Language::Markdown, src);
+        for window in chunks.windows(2) {
+            assert!(window[0].start_byte <= window[1].start_byte);
+        }
+    }
+}
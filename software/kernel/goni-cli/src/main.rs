@@ -4,11 +4,12 @@ use clap::{Parser, Subcommand};
 use futures_util::StreamExt;
 use goni_context::{FacilityLocationSelector, NullKvPager};
 use goni_core::GoniKernel;
+use goni_dataspace::Dataspace;
 use goni_infer::NullLlmEngine;
-use goni_receipts::verify_log;
+use goni_receipts::{load_verifying_keys, verify_log};
 use goni_router::NullRouter;
 use goni_sched::InMemoryScheduler;
-use goni_store::NullDataPlane;
+use goni_store::{DataPlane, InMemorySpineDataPlane, NullDataPlane, QdrantDataPlane};
 use goni_types::TaskClass;
 
 #[derive(Parser)]
@@ -26,6 +27,25 @@ enum Command {
         action: ReceiptCommand,
         #[arg(long, default_value = "./receipts.jsonl")]
         path: String,
+        /// `key_id=base64_public_key` lines used to verify signatures (see `ReceiptCommand::Verify`).
+        #[arg(long, default_value = "./receipt_keys.txt")]
+        keys: String,
+    },
+    /// Replay a JSON workload file against the (stub) LLM engine and record Metrics rows.
+    Workload {
+        #[arg(long)]
+        path: String,
+    },
+    /// Chunk a document and ingest it into the `QDRANT_HTTP_URL` collection, so `rag_candidates`
+    /// can later cite its `source_path`/`start_byte`/`end_byte` provenance.
+    Ingest {
+        /// File to read and chunk.
+        path: String,
+        /// Provenance path recorded on each chunk; defaults to `path`.
+        #[arg(long)]
+        source_path: Option<String>,
+        #[arg(long, default_value = "Chunks")]
+        table: String,
     },
 }
 
@@ -40,6 +60,10 @@ async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
     match cli.command {
         Command::Demo => {
+            // Wires the scheduler to subscription-driven dispatch: `submit_user_query` asserts a
+            // `Tasks` fact into `dataspace` instead of calling `Scheduler::submit` directly.
+            let dataspace = Arc::new(Dataspace::new());
+
             let kernel = GoniKernel::new(
                 Arc::new(NullDataPlane),
                 Arc::new(FacilityLocationSelector::new(0.3)), // gamma hyperparam
@@ -47,12 +71,15 @@ async fn main() -> anyhow::Result<()> {
                 Arc::new(InMemoryScheduler::new()),
                 Arc::new(NullRouter),
                 Arc::new(NullLlmEngine),
-            );
+                Arc::new(goni_embed::LexicalEmbedder::new(1024)),
+            )
+            .with_dataspace(dataspace);
 
             let prompt = "Hello, Goni!";
-            let mut stream = kernel
-                .handle_user_query(prompt, TaskClass::Interactive)
+            let outcome = kernel
+                .handle_user_query(prompt, TaskClass::Interactive, None)
                 .await?;
+            let mut stream = outcome.stream;
 
             println!("Prompt: {prompt}");
 
@@ -61,7 +88,7 @@ async fn main() -> anyhow::Result<()> {
                 print!("{}", tok.text);
             }
         }
-        Command::Receipts { action, path } => match action {
+        Command::Receipts { action, path, keys } => match action {
             ReceiptCommand::Tail { lines } => {
                 let content = std::fs::read_to_string(&path)?;
                 let all: Vec<&str> = content.lines().collect();
@@ -71,10 +98,45 @@ async fn main() -> anyhow::Result<()> {
                 }
             }
             ReceiptCommand::Verify => {
-                verify_log(&path)?;
+                let keys = load_verifying_keys(&keys)?;
+                verify_log(&path, &keys)?;
                 println!("receipt log ok");
             }
         },
+        Command::Ingest { path, source_path, table } => {
+            let text = std::fs::read_to_string(&path)?;
+            let source_path = source_path.unwrap_or_else(|| path.clone());
+            let language = match std::path::Path::new(&source_path).extension().and_then(|e| e.to_str()) {
+                Some("rs") => goni_chunker::Language::Rust,
+                Some("md") => goni_chunker::Language::Markdown,
+                _ => goni_chunker::Language::PlainText,
+            };
+
+            let qdrant_url = std::env::var("QDRANT_HTTP_URL")
+                .map_err(|_| anyhow::anyhow!("QDRANT_HTTP_URL must be set to ingest documents"))?;
+            let embed_dim: usize = std::env::var("EMBED_DIM")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1024);
+            let embedder: Arc<dyn goni_embed::Embedder> = Arc::new(goni_embed::LexicalEmbedder::new(embed_dim));
+            let data_plane = QdrantDataPlane::new(qdrant_url, embedder);
+            let chunker = goni_chunker::HeuristicChunker::new(512, 64);
+
+            data_plane
+                .ingest_document(&table, &source_path, language, &text, &chunker)
+                .await?;
+            println!("ingested {source_path} into table {table}");
+        }
+        Command::Workload { path } => {
+            let data_plane: Arc<dyn goni_store::DataPlane> = Arc::new(InMemorySpineDataPlane::new());
+            let outcomes = goni_workload::replay_workload(path, Arc::new(NullLlmEngine), data_plane).await?;
+            for outcome in &outcomes {
+                println!(
+                    "job {} class={:?} latency_ms={} tokens={} outcome={:?}",
+                    outcome.job_id, outcome.class, outcome.latency_ms, outcome.tokens_emitted, outcome.outcome
+                );
+            }
+        }
     }
 
     Ok(())
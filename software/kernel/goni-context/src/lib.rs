@@ -1,12 +1,19 @@
 use async_trait::async_trait;
 use arrow::array::{
-    Array, Float32Array, FixedSizeListArray, Int32Array, StringArray, UInt32Array,
+    Array, Float32Array, FixedSizeListArray, Int8Array, Int32Array, StringArray, UInt8Array,
+    UInt32Array,
 };
 use arrow::datatypes::DataType;
 use arrow::record_batch::RecordBatch;
+use goni_embed::Embedder;
 use goni_types::{ContextSelection, KvPageId};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
 use thiserror::Error;
 
+mod lru_pager;
+pub use lru_pager::LruKvPager;
+
 /// Minimal view of a candidate chunk for context selection.
 #[derive(Clone)]
 pub struct CandidateChunk<'a> {
@@ -15,6 +22,27 @@ pub struct CandidateChunk<'a> {
     pub tokens: usize,
     pub embedding: &'a [f32],
     pub relevance: f32,
+    /// Source file/document this chunk came from, when ingested via `goni-chunker`.
+    pub source_path: Option<&'a str>,
+    pub start_byte: Option<usize>,
+    pub end_byte: Option<usize>,
+}
+
+impl<'a> CandidateChunk<'a> {
+    /// Copies this borrowed chunk into an [`OwnedCandidateChunk`], for callers that must
+    /// outlive the batch this chunk borrows from.
+    pub fn to_owned_candidate(&self) -> OwnedCandidateChunk {
+        OwnedCandidateChunk {
+            id: self.id.to_string(),
+            text: self.text.map(|s| s.to_string()),
+            tokens: self.tokens,
+            embedding: self.embedding.to_vec(),
+            relevance: self.relevance,
+            source_path: self.source_path.map(|s| s.to_string()),
+            start_byte: self.start_byte,
+            end_byte: self.end_byte,
+        }
+    }
 }
 
 /// Semantic selector – implements the submodular context optimization.
@@ -59,6 +87,12 @@ pub struct KvError {
 // 1) FacilityLocationSelector – real submodular context selector
 //
 
+/// Below this candidate count, [`FacilityLocationSelector`] runs the straightforward eager
+/// greedy pass; at or above it, it switches to lazy (CELF) greedy. Both produce the same
+/// selection (lazy-greedy is exact, just evaluated less eagerly), so this is purely a
+/// small-n/determinism-testing knob, not a quality tradeoff.
+const DEFAULT_LAZY_THRESHOLD: usize = 64;
+
 /// Facility-location based context selector.
 ///
 /// Objective:
@@ -68,11 +102,24 @@ pub struct KvError {
 pub struct FacilityLocationSelector {
     /// Weight on the relevance term (γ).
     gamma: f32,
+    /// See [`DEFAULT_LAZY_THRESHOLD`].
+    lazy_threshold: usize,
 }
 
 impl FacilityLocationSelector {
     pub fn new(gamma: f32) -> Self {
-        Self { gamma }
+        Self {
+            gamma,
+            lazy_threshold: DEFAULT_LAZY_THRESHOLD,
+        }
+    }
+
+    /// Forces the eager-vs-lazy threshold, e.g. down to `0` to always run lazy-greedy or up to
+    /// `usize::MAX` to always run the eager pass (useful for determinism tests that want to
+    /// pin down exactly which path ran).
+    pub fn with_lazy_threshold(mut self, lazy_threshold: usize) -> Self {
+        self.lazy_threshold = lazy_threshold;
+        self
     }
 
     fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
@@ -85,40 +132,26 @@ impl FacilityLocationSelector {
             dot / (norm_a * norm_b)
         }
     }
-}
 
-#[async_trait]
-impl ContextSelector for FacilityLocationSelector {
-    async fn select<'a>(
-        &self,
-        _query_embedding: &[f32],
-        candidates: &[CandidateChunk<'a>],
-        max_tokens: usize,
-    ) -> ContextSelection {
-        let n = candidates.len();
-        if n == 0 || max_tokens == 0 {
-            return ContextSelection {
-                indices: Vec::new(),
-                total_tokens: 0,
-            };
-        }
-
-        // 1) Precompute similarity matrix sim[i][j] = cos(e_i, e_j)
-        let mut sim: Vec<f32> = vec![0.0; n * n];
+    /// Marginal gain of adding candidate `j` to a set whose coverage is `coverage`:
+    /// ΔF = Σ_i (max(cov[i], sim(i,j)) - cov[i]) + γ * relevance_j
+    fn marginal_gain(&self, j: usize, coverage: &[f32], sim: &[f32], n: usize, candidates: &[CandidateChunk<'_>]) -> f32 {
+        let mut gain_cov = 0.0_f32;
         for i in 0..n {
-            for j in 0..n {
-                let s = Self::cosine_similarity(
-                    candidates[i].embedding,
-                    candidates[j].embedding,
-                );
-                sim[i * n + j] = s.max(0.0); // clamp to non-negative
-            }
+            let s = sim[i * n + j];
+            let new_cov = if s > coverage[i] { s } else { coverage[i] };
+            gain_cov += new_cov - coverage[i];
         }
+        gain_cov + self.gamma * candidates[j].relevance
+    }
 
-        // 2) Greedy selection
+    /// Recomputes every unselected candidate's marginal gain on every round — O(n) gain
+    /// evaluations per round, O(n) per evaluation, O(n³) overall. Kept for small `n` (see
+    /// [`DEFAULT_LAZY_THRESHOLD`]) and as the reference implementation lazy-greedy must match.
+    fn select_eager(&self, candidates: &[CandidateChunk<'_>], sim: &[f32], n: usize, max_tokens: usize) -> ContextSelection {
         let mut selected_indices: Vec<u32> = Vec::new();
         let mut selected_mask = vec![false; n];
-        let mut coverage: Vec<f32> = vec![0.0; n]; // cov[i] = max_{j∈S} sim(i,j)
+        let mut coverage: Vec<f32> = vec![0.0; n];
         let mut remaining_tokens = max_tokens;
 
         loop {
@@ -126,25 +159,10 @@ impl ContextSelector for FacilityLocationSelector {
             let mut best_idx: Option<usize> = None;
 
             for j in 0..n {
-                if selected_mask[j] {
+                if selected_mask[j] || candidates[j].tokens > remaining_tokens {
                     continue;
                 }
-
-                let tok = candidates[j].tokens;
-                if tok > remaining_tokens {
-                    continue; // can't fit
-                }
-
-                // Compute marginal gain of adding j:
-                // ΔF = Σ_i (max(cov[i], sim(i,j)) - cov[i]) + γ * relevance_j
-                let mut gain_cov = 0.0_f32;
-                for i in 0..n {
-                    let s = sim[i * n + j];
-                    let new_cov = if s > coverage[i] { s } else { coverage[i] };
-                    gain_cov += new_cov - coverage[i];
-                }
-                let gain = gain_cov + self.gamma * candidates[j].relevance;
-
+                let gain = self.marginal_gain(j, &coverage, sim, n, candidates);
                 if gain > best_gain {
                     best_gain = gain;
                     best_idx = Some(j);
@@ -153,40 +171,141 @@ impl ContextSelector for FacilityLocationSelector {
 
             match best_idx {
                 Some(j) if best_gain > 0.0 => {
-                    // Select j
                     selected_mask[j] = true;
                     selected_indices.push(j as u32);
-                    remaining_tokens =
-                        remaining_tokens.saturating_sub(candidates[j].tokens);
-
-                    // Update coverage array
+                    remaining_tokens = remaining_tokens.saturating_sub(candidates[j].tokens);
                     for i in 0..n {
                         let s = sim[i * n + j];
                         if s > coverage[i] {
                             coverage[i] = s;
                         }
                     }
-
-                    // If we run out of tokens, stop.
                     if remaining_tokens == 0 {
                         break;
                     }
                 }
-                _ => {
-                    // No candidate yields positive marginal gain or fits in budget.
+                _ => break,
+            }
+        }
+
+        finish_selection(candidates, selected_indices)
+    }
+
+    /// Lazy (CELF) greedy: exploits submodularity — since F is monotone submodular, a
+    /// candidate's marginal gain can only shrink as the selected set grows, so a gain computed
+    /// in an earlier round is a valid upper bound on its gain now. A max-heap of
+    /// `(gain, idx, round_stamp)` lets each round pop the candidate with the highest *possible*
+    /// gain; if its stamp is current the bound is exact and it's selected outright, otherwise
+    /// it's refreshed and re-pushed. Most rounds end up recomputing one or a handful of
+    /// candidates instead of all of them.
+    fn select_lazy(&self, candidates: &[CandidateChunk<'_>], sim: &[f32], n: usize, max_tokens: usize) -> ContextSelection {
+        let mut selected_indices: Vec<u32> = Vec::new();
+        let mut coverage: Vec<f32> = vec![0.0; n];
+        let mut remaining_tokens = max_tokens;
+        let mut round: usize = 0;
+
+        // `round_stamp: None` means "never evaluated"; it never matches `Some(round)`, so every
+        // candidate is forced through one real gain computation before it can be selected.
+        let mut heap: BinaryHeap<HeapEntry> = candidates
+            .iter()
+            .enumerate()
+            .map(|(idx, _)| HeapEntry { gain: f32::INFINITY, idx, round_stamp: None })
+            .collect();
+
+        while let Some(entry) = heap.pop() {
+            if entry.gain <= 0.0 {
+                break; // heap max: no remaining candidate can have a larger gain than this.
+            }
+            if candidates[entry.idx].tokens > remaining_tokens {
+                continue; // token budget only shrinks; this candidate can never fit again.
+            }
+            if entry.round_stamp == Some(round) {
+                let j = entry.idx;
+                selected_indices.push(j as u32);
+                remaining_tokens = remaining_tokens.saturating_sub(candidates[j].tokens);
+                for i in 0..n {
+                    let s = sim[i * n + j];
+                    if s > coverage[i] {
+                        coverage[i] = s;
+                    }
+                }
+                round += 1;
+                if remaining_tokens == 0 {
                     break;
                 }
+            } else {
+                let gain = self.marginal_gain(entry.idx, &coverage, sim, n, candidates);
+                heap.push(HeapEntry { gain, idx: entry.idx, round_stamp: Some(round) });
             }
         }
 
-        let total_tokens: usize = selected_indices
-            .iter()
-            .map(|&idx| candidates[idx as usize].tokens)
-            .sum();
+        finish_selection(candidates, selected_indices)
+    }
+}
+
+/// Max-heap entry for [`FacilityLocationSelector::select_lazy`]. Ties on `gain` break in favor
+/// of the lowest `idx` (see `Ord` impl below), matching `select_eager`'s `>` (strict) comparison
+/// which always keeps the first (lowest-index) candidate to reach a given best gain.
+struct HeapEntry {
+    gain: f32,
+    idx: usize,
+    round_stamp: Option<usize>,
+}
 
-        ContextSelection {
-            indices: selected_indices,
-            total_tokens,
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for HeapEntry {}
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.gain.total_cmp(&other.gain).then_with(|| other.idx.cmp(&self.idx))
+    }
+}
+
+fn finish_selection(candidates: &[CandidateChunk<'_>], selected_indices: Vec<u32>) -> ContextSelection {
+    let total_tokens: usize = selected_indices.iter().map(|&idx| candidates[idx as usize].tokens).sum();
+    ContextSelection { indices: selected_indices, total_tokens }
+}
+
+#[async_trait]
+impl ContextSelector for FacilityLocationSelector {
+    async fn select<'a>(
+        &self,
+        _query_embedding: &[f32],
+        candidates: &[CandidateChunk<'a>],
+        max_tokens: usize,
+    ) -> ContextSelection {
+        let n = candidates.len();
+        if n == 0 || max_tokens == 0 {
+            return ContextSelection {
+                indices: Vec::new(),
+                total_tokens: 0,
+            };
+        }
+
+        // Precompute similarity matrix sim[i][j] = cos(e_i, e_j)
+        let mut sim: Vec<f32> = vec![0.0; n * n];
+        for i in 0..n {
+            for j in 0..n {
+                let s = Self::cosine_similarity(
+                    candidates[i].embedding,
+                    candidates[j].embedding,
+                );
+                sim[i * n + j] = s.max(0.0); // clamp to non-negative
+            }
+        }
+
+        if n < self.lazy_threshold {
+            self.select_eager(candidates, &sim, n, max_tokens)
+        } else {
+            self.select_lazy(candidates, &sim, n, max_tokens)
         }
     }
 }
@@ -203,6 +322,10 @@ pub enum CandidateBuildError {
     InvalidColumnType(String),
     #[error("embedding dimension mismatch; expected {expected}, got {actual}")]
     EmbeddingDimMismatch { expected: usize, actual: usize },
+    #[error("embedder error: {0}")]
+    Embedder(String),
+    #[error("unsupported quantization for column '{0}'")]
+    UnsupportedQuantization(String),
 }
 
 /// Cosine similarity used for relevance.
@@ -237,6 +360,21 @@ pub fn record_batch_to_candidate_chunks<'a>(
     embedding_col: &str,
     query_embedding: &[f32],
 ) -> Result<Vec<CandidateChunk<'a>>, CandidateBuildError> {
+    Ok(
+        build_candidate_chunks_with_rows(batch, id_col, tokens_col, embedding_col, query_embedding)?
+            .into_iter()
+            .map(|(_row, chunk)| chunk)
+            .collect(),
+    )
+}
+
+fn build_candidate_chunks_with_rows<'a>(
+    batch: &'a RecordBatch,
+    id_col: &str,
+    tokens_col: &str,
+    embedding_col: &str,
+    query_embedding: &[f32],
+) -> Result<Vec<(usize, CandidateChunk<'a>)>, CandidateBuildError> {
     let schema = batch.schema();
 
     // 1) Locate columns by name
@@ -250,6 +388,9 @@ pub fn record_batch_to_candidate_chunks<'a>(
         CandidateBuildError::MissingColumn(embedding_col.to_string())
     })?;
     let text_idx = schema.index_of("text").ok();
+    let source_path_idx = schema.index_of("source_path").ok();
+    let start_byte_idx = schema.index_of("start_byte").ok();
+    let end_byte_idx = schema.index_of("end_byte").ok();
 
     // 2) Downcast columns
 
@@ -265,6 +406,21 @@ pub fn record_batch_to_candidate_chunks<'a>(
         None => None,
     };
 
+    // optional provenance: source path + byte offsets, present when the row was produced by
+    // `goni-chunker` (see `QdrantDataPlane::ingest_document`).
+    let source_path_array: Option<&StringArray> = match source_path_idx {
+        Some(idx) => batch.column(idx).as_any().downcast_ref::<StringArray>(),
+        None => None,
+    };
+    let start_byte_array: Option<&UInt32Array> = match start_byte_idx {
+        Some(idx) => batch.column(idx).as_any().downcast_ref::<UInt32Array>(),
+        None => None,
+    };
+    let end_byte_array: Option<&UInt32Array> = match end_byte_idx {
+        Some(idx) => batch.column(idx).as_any().downcast_ref::<UInt32Array>(),
+        None => None,
+    };
+
     // tokens: Int32 or UInt32 → usize
     let tokens_array = batch.column(tokens_idx);
     let tokens_type = tokens_array.data_type();
@@ -339,12 +495,373 @@ pub fn record_batch_to_candidate_chunks<'a>(
         // 6) relevance = cos(query_embedding, embedding)
         let relevance = cosine_similarity(query_embedding, emb_slice);
 
-        chunks.push(CandidateChunk {
-            id: id_str,
-            text: text_val,
+        let source_path =
+            source_path_array.and_then(|arr| if arr.is_null(row) { None } else { Some(arr.value(row)) });
+        let start_byte =
+            start_byte_array.and_then(|arr| if arr.is_null(row) { None } else { Some(arr.value(row) as usize) });
+        let end_byte =
+            end_byte_array.and_then(|arr| if arr.is_null(row) { None } else { Some(arr.value(row) as usize) });
+
+        chunks.push((
+            row,
+            CandidateChunk {
+                id: id_str,
+                text: text_val,
+                tokens,
+                embedding: emb_slice,
+                relevance,
+                source_path,
+                start_byte,
+                end_byte,
+            },
+        ));
+    }
+
+    Ok(chunks)
+}
+
+/// Owned counterpart to [`CandidateChunk`], for paths that compute new buffers (e.g.
+/// auto-embedding) rather than borrowing from an existing `RecordBatch`.
+#[derive(Debug, Clone)]
+pub struct OwnedCandidateChunk {
+    pub id: String,
+    pub text: Option<String>,
+    pub tokens: usize,
+    pub embedding: Vec<f32>,
+    pub relevance: f32,
+    pub source_path: Option<String>,
+    pub start_byte: Option<usize>,
+    pub end_byte: Option<usize>,
+}
+
+impl OwnedCandidateChunk {
+    /// Borrows this as a [`CandidateChunk`] for use with [`ContextSelector::select`].
+    pub fn as_candidate_chunk(&self) -> CandidateChunk<'_> {
+        CandidateChunk {
+            id: &self.id,
+            text: self.text.as_deref(),
+            tokens: self.tokens,
+            embedding: &self.embedding,
+            relevance: self.relevance,
+            source_path: self.source_path.as_deref(),
+            start_byte: self.start_byte,
+            end_byte: self.end_byte,
+        }
+    }
+}
+
+/// Like [`record_batch_to_candidate_chunks`], but for tables that carry no embedding column at
+/// all — only `text`. Auto-embeds each row's text (and `query_text`, since there's no
+/// precomputed query vector to hand it either) via `embedder`, so the selector is usable
+/// directly on keyword-indexed data without a separate embedding pipeline. Returns the query
+/// embedding alongside the chunks so the caller can pass it straight to
+/// `ContextSelector::select` without re-embedding the query itself.
+///
+/// Unlike the zero-copy converter, the resulting embeddings aren't Arrow buffers to borrow from,
+/// so this owns them via [`OwnedCandidateChunk`] rather than [`CandidateChunk`].
+pub async fn record_batch_to_candidate_chunks_auto_embed(
+    batch: &RecordBatch,
+    id_col: &str,
+    tokens_col: &str,
+    text_col: &str,
+    query_text: &str,
+    embedder: &dyn Embedder,
+) -> Result<(Vec<f32>, Vec<OwnedCandidateChunk>), CandidateBuildError> {
+    let schema = batch.schema();
+
+    let id_idx = schema
+        .index_of(id_col)
+        .map_err(|_| CandidateBuildError::MissingColumn(id_col.to_string()))?;
+    let tokens_idx = schema
+        .index_of(tokens_col)
+        .map_err(|_| CandidateBuildError::MissingColumn(tokens_col.to_string()))?;
+    let text_idx = schema
+        .index_of(text_col)
+        .map_err(|_| CandidateBuildError::MissingColumn(text_col.to_string()))?;
+    let source_path_idx = schema.index_of("source_path").ok();
+    let start_byte_idx = schema.index_of("start_byte").ok();
+    let end_byte_idx = schema.index_of("end_byte").ok();
+
+    let id_array = batch
+        .column(id_idx)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .ok_or_else(|| CandidateBuildError::InvalidColumnType(id_col.to_string()))?;
+    let text_array = batch
+        .column(text_idx)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .ok_or_else(|| CandidateBuildError::InvalidColumnType(text_col.to_string()))?;
+    let source_path_array: Option<&StringArray> = match source_path_idx {
+        Some(idx) => batch.column(idx).as_any().downcast_ref::<StringArray>(),
+        None => None,
+    };
+    let start_byte_array: Option<&UInt32Array> = match start_byte_idx {
+        Some(idx) => batch.column(idx).as_any().downcast_ref::<UInt32Array>(),
+        None => None,
+    };
+    let end_byte_array: Option<&UInt32Array> = match end_byte_idx {
+        Some(idx) => batch.column(idx).as_any().downcast_ref::<UInt32Array>(),
+        None => None,
+    };
+
+    let tokens_array = batch.column(tokens_idx);
+    let tokens_type = tokens_array.data_type();
+
+    let num_rows = batch.num_rows();
+    let mut rows: Vec<usize> = Vec::with_capacity(num_rows);
+    let mut texts: Vec<&str> = Vec::with_capacity(num_rows);
+    let mut tokens_by_row: Vec<usize> = Vec::with_capacity(num_rows);
+
+    for row in 0..num_rows {
+        if id_array.is_null(row) || text_array.is_null(row) {
+            continue;
+        }
+        let tokens: usize = match tokens_type {
+            DataType::Int32 => {
+                let ints = tokens_array
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .ok_or_else(|| CandidateBuildError::InvalidColumnType(tokens_col.to_string()))?;
+                let v = ints.value(row);
+                if v <= 0 {
+                    continue;
+                }
+                v as usize
+            }
+            DataType::UInt32 => {
+                let ints = tokens_array
+                    .as_any()
+                    .downcast_ref::<UInt32Array>()
+                    .ok_or_else(|| CandidateBuildError::InvalidColumnType(tokens_col.to_string()))?;
+                ints.value(row) as usize
+            }
+            _ => return Err(CandidateBuildError::InvalidColumnType(tokens_col.to_string())),
+        };
+
+        rows.push(row);
+        texts.push(text_array.value(row));
+        tokens_by_row.push(tokens);
+    }
+
+    // One batched embed call for every surviving row's text, plus the query: cheaper than N+1
+    // round trips to a remote embedder, and keeps the query in the same embedding space.
+    let mut embed_inputs: Vec<&str> = texts.clone();
+    embed_inputs.push(query_text);
+    let mut embeddings = embedder
+        .embed_batch(&embed_inputs)
+        .await
+        .map_err(|e| CandidateBuildError::Embedder(e.to_string()))?;
+    let query_embedding = embeddings.pop().unwrap_or_default();
+
+    let chunks = rows
+        .into_iter()
+        .zip(tokens_by_row)
+        .zip(embeddings)
+        .map(|((row, tokens), embedding)| {
+            let relevance = cosine_similarity(&query_embedding, &embedding);
+            OwnedCandidateChunk {
+                id: id_array.value(row).to_string(),
+                text: Some(text_array.value(row).to_string()),
+                tokens,
+                embedding,
+                relevance,
+                source_path: source_path_array
+                    .and_then(|arr| if arr.is_null(row) { None } else { Some(arr.value(row).to_string()) }),
+                start_byte: start_byte_array.and_then(|arr| if arr.is_null(row) { None } else { Some(arr.value(row) as usize) }),
+                end_byte: end_byte_array.and_then(|arr| if arr.is_null(row) { None } else { Some(arr.value(row) as usize) }),
+            }
+        })
+        .collect();
+
+    Ok((query_embedding, chunks))
+}
+
+/// Scalar quantization scheme for an `embedding_col` stored as `FixedSizeList<Int8>` or
+/// `FixedSizeList<UInt8>`: `dequantized = (raw - zero_point) * scale`.
+#[derive(Debug, Clone)]
+pub enum Quantization {
+    /// One `(scale, zero_point)` pair applied to every row in the column.
+    Global { scale: f32, zero_point: i32 },
+    /// A per-row scale column (e.g. `embedding_scale`) read alongside `embedding_col`, with an
+    /// optional per-row `Int32` zero-point column; `zero_point` defaults to `0` when absent
+    /// (plain symmetric quantization).
+    PerVector { scale_col: String, zero_point_col: Option<String> },
+}
+
+/// Like [`record_batch_to_candidate_chunks`], but for `embedding_col` stored as
+/// `FixedSizeList<Int8>` or `FixedSizeList<UInt8>` — the compact layout many columnar vector
+/// stores use — dequantizing each row to `f32` via `quantization` before computing relevance.
+/// Dequantized vectors can't be borrowed zero-copy from the Int8/UInt8 buffer, so (like
+/// [`record_batch_to_candidate_chunks_auto_embed`]) this returns owned candidates.
+pub fn record_batch_to_candidate_chunks_quantized(
+    batch: &RecordBatch,
+    id_col: &str,
+    tokens_col: &str,
+    embedding_col: &str,
+    query_embedding: &[f32],
+    quantization: &Quantization,
+) -> Result<Vec<OwnedCandidateChunk>, CandidateBuildError> {
+    let schema = batch.schema();
+
+    let id_idx = schema
+        .index_of(id_col)
+        .map_err(|_| CandidateBuildError::MissingColumn(id_col.to_string()))?;
+    let tokens_idx = schema
+        .index_of(tokens_col)
+        .map_err(|_| CandidateBuildError::MissingColumn(tokens_col.to_string()))?;
+    let emb_idx = schema
+        .index_of(embedding_col)
+        .map_err(|_| CandidateBuildError::MissingColumn(embedding_col.to_string()))?;
+    let text_idx = schema.index_of("text").ok();
+    let source_path_idx = schema.index_of("source_path").ok();
+    let start_byte_idx = schema.index_of("start_byte").ok();
+    let end_byte_idx = schema.index_of("end_byte").ok();
+
+    let id_array = batch
+        .column(id_idx)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .ok_or_else(|| CandidateBuildError::InvalidColumnType(id_col.to_string()))?;
+    let text_array: Option<&StringArray> = match text_idx {
+        Some(idx) => batch.column(idx).as_any().downcast_ref::<StringArray>(),
+        None => None,
+    };
+    let source_path_array: Option<&StringArray> = match source_path_idx {
+        Some(idx) => batch.column(idx).as_any().downcast_ref::<StringArray>(),
+        None => None,
+    };
+    let start_byte_array: Option<&UInt32Array> = match start_byte_idx {
+        Some(idx) => batch.column(idx).as_any().downcast_ref::<UInt32Array>(),
+        None => None,
+    };
+    let end_byte_array: Option<&UInt32Array> = match end_byte_idx {
+        Some(idx) => batch.column(idx).as_any().downcast_ref::<UInt32Array>(),
+        None => None,
+    };
+
+    let tokens_array = batch.column(tokens_idx);
+    let tokens_type = tokens_array.data_type();
+
+    let emb_array = batch.column(emb_idx);
+    let emb_list = emb_array
+        .as_any()
+        .downcast_ref::<FixedSizeListArray>()
+        .ok_or_else(|| CandidateBuildError::InvalidColumnType(embedding_col.to_string()))?;
+    let value_len = emb_list.value_length() as usize;
+    if value_len != query_embedding.len() {
+        return Err(CandidateBuildError::EmbeddingDimMismatch {
+            expected: query_embedding.len(),
+            actual: value_len,
+        });
+    }
+    let emb_values = emb_list.values();
+    enum RawEmbeddings<'a> {
+        I8(&'a [i8]),
+        U8(&'a [u8]),
+    }
+    let raw_embeddings = if let Some(arr) = emb_values.as_any().downcast_ref::<Int8Array>() {
+        RawEmbeddings::I8(arr.values().as_ref())
+    } else if let Some(arr) = emb_values.as_any().downcast_ref::<UInt8Array>() {
+        RawEmbeddings::U8(arr.values().as_ref())
+    } else {
+        return Err(CandidateBuildError::UnsupportedQuantization(embedding_col.to_string()));
+    };
+
+    // Per-vector scale/zero-point columns, when used, must line up with the embedding rows.
+    let (scale_array, zero_point_array): (Option<&Float32Array>, Option<&Int32Array>) = match quantization {
+        Quantization::Global { .. } => (None, None),
+        Quantization::PerVector { scale_col, zero_point_col } => {
+            let scale_idx = schema
+                .index_of(scale_col)
+                .map_err(|_| CandidateBuildError::MissingColumn(scale_col.to_string()))?;
+            let scale_array = batch
+                .column(scale_idx)
+                .as_any()
+                .downcast_ref::<Float32Array>()
+                .ok_or_else(|| CandidateBuildError::InvalidColumnType(scale_col.to_string()))?;
+            let zero_point_array = match zero_point_col {
+                Some(col) => {
+                    let idx = schema
+                        .index_of(col)
+                        .map_err(|_| CandidateBuildError::MissingColumn(col.to_string()))?;
+                    Some(
+                        batch
+                            .column(idx)
+                            .as_any()
+                            .downcast_ref::<Int32Array>()
+                            .ok_or_else(|| CandidateBuildError::InvalidColumnType(col.to_string()))?,
+                    )
+                }
+                None => None,
+            };
+            (Some(scale_array), zero_point_array)
+        }
+    };
+
+    let num_rows = batch.num_rows();
+    let mut chunks = Vec::with_capacity(num_rows);
+
+    for row in 0..num_rows {
+        if id_array.is_null(row) {
+            continue;
+        }
+
+        let tokens: usize = match tokens_type {
+            DataType::Int32 => {
+                let ints = tokens_array
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .ok_or_else(|| CandidateBuildError::InvalidColumnType(tokens_col.to_string()))?;
+                let v = ints.value(row);
+                if v <= 0 {
+                    continue;
+                }
+                v as usize
+            }
+            DataType::UInt32 => {
+                let ints = tokens_array
+                    .as_any()
+                    .downcast_ref::<UInt32Array>()
+                    .ok_or_else(|| CandidateBuildError::InvalidColumnType(tokens_col.to_string()))?;
+                ints.value(row) as usize
+            }
+            _ => return Err(CandidateBuildError::InvalidColumnType(tokens_col.to_string())),
+        };
+
+        let (scale, zero_point) = match quantization {
+            Quantization::Global { scale, zero_point } => (*scale, *zero_point),
+            Quantization::PerVector { .. } => (
+                scale_array.expect("scale_array set for PerVector quantization").value(row),
+                zero_point_array.map(|arr| arr.value(row)).unwrap_or(0),
+            ),
+        };
+
+        let start = row * value_len;
+        let end = start + value_len;
+        let embedding: Vec<f32> = match &raw_embeddings {
+            RawEmbeddings::I8(values) => values[start..end]
+                .iter()
+                .map(|&v| (v as i32 - zero_point) as f32 * scale)
+                .collect(),
+            RawEmbeddings::U8(values) => values[start..end]
+                .iter()
+                .map(|&v| (v as i32 - zero_point) as f32 * scale)
+                .collect(),
+        };
+
+        let relevance = cosine_similarity(query_embedding, &embedding);
+
+        chunks.push(OwnedCandidateChunk {
+            id: id_array.value(row).to_string(),
+            text: text_array.and_then(|arr| if arr.is_null(row) { None } else { Some(arr.value(row).to_string()) }),
             tokens,
-            embedding: emb_slice,
+            embedding,
             relevance,
+            source_path: source_path_array
+                .and_then(|arr| if arr.is_null(row) { None } else { Some(arr.value(row).to_string()) }),
+            start_byte: start_byte_array.and_then(|arr| if arr.is_null(row) { None } else { Some(arr.value(row) as usize) }),
+            end_byte: end_byte_array.and_then(|arr| if arr.is_null(row) { None } else { Some(arr.value(row) as usize) }),
         });
     }
 
@@ -397,6 +914,9 @@ mod tests {
                 tokens: 3,
                 embedding: &[1.0, 0.0],
                 relevance: 0.9,
+                source_path: None,
+                start_byte: None,
+                end_byte: None,
             },
             CandidateChunk {
                 id: "b",
@@ -404,6 +924,9 @@ mod tests {
                 tokens: 2,
                 embedding: &[0.0, 1.0],
                 relevance: 0.8,
+                source_path: None,
+                start_byte: None,
+                end_byte: None,
             },
             CandidateChunk {
                 id: "c",
@@ -411,6 +934,9 @@ mod tests {
                 tokens: 10,
                 embedding: &[0.7, 0.7],
                 relevance: 0.5,
+                source_path: None,
+                start_byte: None,
+                end_byte: None,
             },
         ];
 
@@ -421,4 +947,124 @@ mod tests {
         assert_eq!(sel1.indices, sel2.indices, "deterministic selection");
         assert!(!sel1.indices.is_empty(), "should select at least one chunk");
     }
+
+    #[tokio::test]
+    async fn lazy_greedy_matches_eager_greedy() {
+        let query = vec![1.0_f32, 0.0];
+        let embeddings: Vec<[f32; 2]> = (0..40u32)
+            .map(|i| {
+                let angle = (i as f32) * 0.05;
+                [angle.cos(), angle.sin()]
+            })
+            .collect();
+        let candidates: Vec<CandidateChunk> = embeddings
+            .iter()
+            .enumerate()
+            .map(|(i, embedding)| CandidateChunk {
+                id: "synthetic",
+                text: None,
+                tokens: 1 + (i % 5),
+                embedding,
+                relevance: 1.0 / (1.0 + i as f32),
+                source_path: None,
+                start_byte: None,
+                end_byte: None,
+            })
+            .collect();
+
+        let eager = FacilityLocationSelector::new(0.2).with_lazy_threshold(usize::MAX);
+        let lazy = FacilityLocationSelector::new(0.2).with_lazy_threshold(0);
+
+        let eager_sel = eager.select(&query, &candidates, 20).await;
+        let lazy_sel = lazy.select(&query, &candidates, 20).await;
+
+        assert_eq!(eager_sel.indices, lazy_sel.indices);
+        assert_eq!(eager_sel.total_tokens, lazy_sel.total_tokens);
+    }
+
+    #[tokio::test]
+    async fn auto_embed_falls_back_to_text_when_no_embedding_column() {
+        use arrow::array::{StringArray, UInt32Array};
+        use arrow::datatypes::{DataType, Field, Schema};
+        use goni_embed::LexicalEmbedder;
+        use std::sync::Arc;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("text", DataType::Utf8, false),
+            Field::new("tokens", DataType::UInt32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec!["a", "b"])),
+                Arc::new(StringArray::from(vec!["the quick fox", "completely unrelated topic"])),
+                Arc::new(UInt32Array::from(vec![3u32, 4u32])),
+            ],
+        )
+        .unwrap();
+
+        let embedder = LexicalEmbedder::new(32);
+        let (query_embedding, chunks) =
+            record_batch_to_candidate_chunks_auto_embed(&batch, "id", "tokens", "text", "the quick fox", &embedder)
+                .await
+                .unwrap();
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(query_embedding.len(), 32);
+        // The chunk whose text matches the query verbatim should score as (near-)identical.
+        let exact_match = chunks.iter().find(|c| c.id == "a").unwrap();
+        assert!(exact_match.relevance > 0.99);
+    }
+
+    #[test]
+    fn quantized_global_scale_dequantizes_and_matches_float_cosine() {
+        use arrow::array::{FixedSizeListArray, Int8Array, StringArray, UInt32Array};
+        use arrow::datatypes::{DataType, Field, Schema};
+        use std::sync::Arc;
+
+        // scale = 1/127 maps Int8 range [-127, 127] back onto [-1.0, 1.0].
+        let scale = 1.0 / 127.0;
+        let raw: Vec<i8> = vec![127, 0, -127, 0];
+        let field = Arc::new(Field::new("item", DataType::Int8, false));
+        let emb_array = FixedSizeListArray::try_new(field, 2, Arc::new(Int8Array::from(raw)), None).unwrap();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("tokens", DataType::UInt32, false),
+            Field::new(
+                "embedding",
+                DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Int8, false)), 2),
+                false,
+            ),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec!["a", "b"])),
+                Arc::new(UInt32Array::from(vec![3u32, 3u32])),
+                Arc::new(emb_array),
+            ],
+        )
+        .unwrap();
+
+        let query_embedding = [1.0_f32, 0.0];
+        let quantization = Quantization::Global { scale, zero_point: 0 };
+        let chunks = record_batch_to_candidate_chunks_quantized(
+            &batch,
+            "id",
+            "tokens",
+            "embedding",
+            &query_embedding,
+            &quantization,
+        )
+        .unwrap();
+
+        assert_eq!(chunks.len(), 2);
+        // Row "a" dequantizes to ~[1.0, 0.0] — parallel to the query, so cosine ~= 1.0.
+        assert!((chunks[0].relevance - 1.0).abs() < 1e-3);
+        // Row "b" dequantizes to ~[-1.0, 0.0] — anti-parallel, so cosine ~= -1.0.
+        assert!((chunks[1].relevance + 1.0).abs() < 1e-3);
+    }
+
 }
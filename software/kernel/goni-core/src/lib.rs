@@ -1,19 +1,103 @@
 use std::{collections::HashMap, sync::Arc};
 
-use goni_context::{record_batch_to_candidate_chunks, CandidateChunk, ContextSelector, KvPager};
-use goni_infer::{LlmEngine, TokenStream};
+use futures_util::{stream, StreamExt};
+use goni_context::{
+    record_batch_to_candidate_chunks, record_batch_to_candidate_chunks_auto_embed,
+    record_batch_to_candidate_chunks_quantized, CandidateChunk, ContextSelector, KvPager,
+    OwnedCandidateChunk, Quantization,
+};
+use goni_dataspace::{Dataspace, Fact, FieldValue};
+use goni_embed::Embedder;
+use goni_infer::{LlmEngine, LlmToken, TokenStream};
 use goni_router::{EscalationPolicy, Router};
+use goni_sched::dataspace::subscribe_queued_tasks;
 use goni_sched::Scheduler;
-use goni_store::DataPlane;
-use goni_types::{ContextSelection, LlmRequest, TaskClass};
+use goni_store::{DataPlane, RagFilter};
+use goni_types::{BatchMeta, ContextSelection, LlmRequest, ModelTier, TaskClass};
 
 use tokio::sync::{oneshot, Mutex};
 use uuid::Uuid;
 
+pub mod sprt;
+use sprt::{log_likelihood_ratio_delta, SprtDecision, SprtState};
+
+/// A cap on how many tokens an [`EscalationPolicy::SprtThreshold`] test may observe before it's
+/// forced to a decision by the sign of Λ (see `SprtState`'s `max_observations`).
+const SPRT_MAX_OBSERVATIONS: usize = 256;
+
+/// Shared handle a [`SolveOutcome`]'s consumer can poll to see whether, and to which tier,
+/// generation was escalated mid-stream. Written from inside the escalation-aware token stream
+/// as soon as the SPRT test decides to escalate, so it's readable once the caller has drained
+/// (or even partway through draining) the stream.
+#[derive(Clone, Default)]
+pub struct EscalationHandle(Arc<std::sync::Mutex<Option<ModelTier>>>);
+
+impl EscalationHandle {
+    pub fn get(&self) -> Option<ModelTier> {
+        *self.0.lock().unwrap()
+    }
+
+    fn set(&self, tier: ModelTier) {
+        *self.0.lock().unwrap() = Some(tier);
+    }
+}
+
+/// What [`GoniKernel::solve_prompt`] (and therefore [`GoniKernel::handle_user_query`]) resolves
+/// to: the token stream plus a handle recording whether it was escalated to a higher tier.
+pub struct SolveOutcome {
+    pub stream: TokenStream,
+    pub escalation: EscalationHandle,
+}
+
 struct PendingRequest {
     prompt: String,
-    class: TaskClass,
-    tx: oneshot::Sender<anyhow::Result<TokenStream>>,
+    /// Full scheduler metadata for this request, including the `dequeue_ts` the scheduler stamps
+    /// in `Scheduler::next` — `solve_prompt` needs it to report service time back via
+    /// `Scheduler::report_complete` once generation finishes.
+    meta: BatchMeta,
+    /// Optional retrieval scope (tenant/language/path/etc.), see [`RagFilter`].
+    scope: Option<RagFilter>,
+    tx: oneshot::Sender<anyhow::Result<SolveOutcome>>,
+}
+
+/// `Tasks.queue_id` spelling for `class`, matching `goni_workload::parse_class`'s inverse.
+fn queue_id(class: TaskClass) -> &'static str {
+    match class {
+        TaskClass::Interactive => "interactive",
+        TaskClass::Background => "background",
+        TaskClass::Maintenance => "maintenance",
+    }
+}
+
+/// Builds the `Tasks` fact `with_dataspace`-enabled submission asserts: a `Queued`-state row
+/// carrying just enough of `meta` for `goni_sched::dataspace::submit_from_event` to rebuild an
+/// equivalent `GoniBatch` on the other side of the subscription.
+fn queued_task_fact(meta: &BatchMeta) -> Fact {
+    let mut fields = std::collections::BTreeMap::new();
+    fields.insert("state".to_string(), FieldValue::Str("Queued".to_string()));
+    fields.insert("task_id".to_string(), FieldValue::Str(meta.id.to_string()));
+    fields.insert("queue_id".to_string(), FieldValue::Str(queue_id(meta.class).to_string()));
+    fields.insert(
+        "expected_cost_tokens".to_string(),
+        FieldValue::Int(meta.est_tokens as i64),
+    );
+    Fact {
+        kind: "Tasks".to_string(),
+        plane: "Control".to_string(),
+        fields,
+        row: Arc::new(arrow::record_batch::RecordBatch::new_empty(Arc::new(
+            arrow::datatypes::Schema::empty(),
+        ))),
+    }
+}
+
+/// The next tier up the escalation ladder, or `None` if `tier` is already the highest.
+fn next_tier(tier: ModelTier) -> Option<ModelTier> {
+    match tier {
+        ModelTier::LocalSmall => Some(ModelTier::LocalLarge),
+        ModelTier::LocalLarge => Some(ModelTier::RemoteHeavy),
+        ModelTier::RemoteHeavy => None,
+    }
 }
 
 /// The orchestrator/kernel: wires the planes together.
@@ -24,11 +108,19 @@ pub struct GoniKernel {
     pub scheduler: Arc<dyn Scheduler>,
     pub router: Arc<dyn Router>,
     pub llm_engine: Arc<dyn LlmEngine>,
+    /// Shared embedder: the same instance must be used for ingestion and query so their
+    /// vectors share one dimension and one semantic space.
+    pub embedder: Arc<dyn Embedder>,
 
     /// Requests waiting to be executed by the scheduler loop.
     ///
     /// Key: batch_id (scheduler meta id)
     pending: Mutex<HashMap<Uuid, PendingRequest>>,
+
+    /// When set (see `with_dataspace`), `submit_user_query` asserts a `Tasks` fact instead of
+    /// calling `scheduler.submit` directly, so the scheduler is driven by the dataspace's
+    /// subscription dispatch rather than a direct method call from the kernel.
+    dataspace: Option<(Arc<Dataspace>, goni_dataspace::ObserverId)>,
 }
 
 impl GoniKernel {
@@ -39,6 +131,7 @@ impl GoniKernel {
         scheduler: Arc<dyn Scheduler>,
         router: Arc<dyn Router>,
         llm_engine: Arc<dyn LlmEngine>,
+        embedder: Arc<dyn Embedder>,
     ) -> Self {
         Self {
             data_plane,
@@ -47,16 +140,37 @@ impl GoniKernel {
             scheduler,
             router,
             llm_engine,
+            embedder,
             pending: Mutex::new(HashMap::new()),
+            dataspace: None,
         }
     }
 
+    /// Routes `submit_user_query`'s submissions through `dataspace` as asserted `Tasks` facts
+    /// instead of calling `self.scheduler.submit` directly, subscribing the scheduler to
+    /// `Queued`-state facts the same way `goni-sched::dataspace::subscribe_queued_tasks` does
+    /// for any other subscriber.
+    pub fn with_dataspace(mut self, dataspace: Arc<Dataspace>) -> Self {
+        let observer = subscribe_queued_tasks(&dataspace);
+        self.dataspace = Some((dataspace, observer));
+        self
+    }
+
     /// High-level API: enqueue a query and await the solver result.
     ///
+    /// `scope`, when set, restricts `rag_candidates` to the matching subset of the collection
+    /// (e.g. one tenant) before vector scoring — essential once multiple projects/tenants share
+    /// one collection. Pass `None` to search the whole collection.
+    ///
     /// Important: this method does **not** call the LLM directly.
     /// The LLM is invoked by the scheduler executor loop (see `run_scheduler_loop`).
-    pub async fn handle_user_query(&self, prompt: &str, class: TaskClass) -> anyhow::Result<TokenStream> {
-        let (batch_id, rx) = self.submit_user_query(prompt, class).await?;
+    pub async fn handle_user_query(
+        &self,
+        prompt: &str,
+        class: TaskClass,
+        scope: Option<RagFilter>,
+    ) -> anyhow::Result<SolveOutcome> {
+        let (batch_id, rx) = self.submit_user_query(prompt, class, scope).await?;
         // In MVP, if the executor loop is not running, run one step inline.
         // This keeps CLI/dev usage working while preserving the architectural boundary.
         if self.pending.lock().await.contains_key(&batch_id) {
@@ -65,43 +179,60 @@ impl GoniKernel {
         rx.await?
     }
 
-    /// Submit a user query into the scheduler and return a oneshot that yields the token stream.
+    /// Submit a user query into the scheduler and return a oneshot that yields the solve outcome.
     pub async fn submit_user_query(
         &self,
         prompt: &str,
         class: TaskClass,
-    ) -> anyhow::Result<(Uuid, oneshot::Receiver<anyhow::Result<TokenStream>>)> {
+        scope: Option<RagFilter>,
+    ) -> anyhow::Result<(Uuid, oneshot::Receiver<anyhow::Result<SolveOutcome>>)> {
         let batch_id = Uuid::new_v4();
         let (tx, rx) = oneshot::channel();
 
+        let meta = BatchMeta {
+            id: batch_id,
+            class,
+            arrival_ts: std::time::Instant::now(),
+            est_tokens: prompt.split_whitespace().count().max(1),
+            dequeue_ts: None,
+        };
+
         {
             let mut pending = self.pending.lock().await;
             pending.insert(
                 batch_id,
                 PendingRequest {
                     prompt: prompt.to_string(),
-                    class,
+                    meta: meta.clone(),
+                    scope,
                     tx,
                 },
             );
         }
 
-        // Submit a minimal batch (payload-free for MVP). Scheduler sees meta only.
-        let schema = Arc::new(arrow::datatypes::Schema::empty());
-        let empty = arrow::record_batch::RecordBatch::new_empty(schema);
-        let batch = goni_types::GoniBatch {
-            data: Arc::new(empty),
-            meta: goni_types::BatchMeta {
-                id: batch_id,
-                class,
-                arrival_ts: std::time::Instant::now(),
-                est_tokens: prompt.split_whitespace().count().max(1),
-            },
-        };
-        self.scheduler
-            .submit(batch)
-            .await
-            .map_err(|e| anyhow::anyhow!(e.message))?;
+        match &self.dataspace {
+            Some((dataspace, observer)) => {
+                let events = dataspace.assert(queued_task_fact(&meta));
+                for event in events {
+                    goni_sched::dataspace::submit_from_event(self.scheduler.as_ref(), *observer, &event)
+                        .await
+                        .map_err(|e| anyhow::anyhow!(e.message))?;
+                }
+            }
+            None => {
+                // Submit a minimal batch (payload-free for MVP). Scheduler sees meta only.
+                let schema = Arc::new(arrow::datatypes::Schema::empty());
+                let empty = arrow::record_batch::RecordBatch::new_empty(schema);
+                let batch = goni_types::GoniBatch {
+                    data: Arc::new(empty),
+                    meta,
+                };
+                self.scheduler
+                    .submit(batch)
+                    .await
+                    .map_err(|e| anyhow::anyhow!(e.message))?;
+            }
+        }
 
         Ok((batch_id, rx))
     }
@@ -115,7 +246,9 @@ impl GoniKernel {
         };
         let Some(req) = pending else { return; };
 
-        let result = self.solve_prompt(&req.prompt, req.class).await;
+        // Use the scheduler's copy of the metadata, not the one captured at submit time: `next`
+        // stamps `dequeue_ts` on its way out, which `solve_prompt` needs to measure service time.
+        let result = self.solve_prompt(&req.prompt, batch.meta, req.scope).await;
         let _ = req.tx.send(result);
     }
 
@@ -128,35 +261,51 @@ impl GoniKernel {
         }
     }
 
-    async fn solve_prompt(&self, prompt: &str, _class: TaskClass) -> anyhow::Result<TokenStream> {
-        // Deterministic lexical embedding baseline.
-        let emb_dim: usize = std::env::var("EMBED_DIM")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(1024);
-        let query_embedding = goni_embed::embed(prompt, emb_dim);
+    async fn solve_prompt(
+        &self,
+        prompt: &str,
+        meta: BatchMeta,
+        scope: Option<RagFilter>,
+    ) -> anyhow::Result<SolveOutcome> {
+        let query_embedding = self
+            .embedder
+            .embed_batch(&[prompt])
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
         let collection = std::env::var("QDRANT_COLLECTION").unwrap_or_else(|_| "default".into());
 
-        // Fetch candidates from the data plane (Qdrant-backed when configured).
+        // Fetch candidates from the data plane (Qdrant-backed when configured), scoped to
+        // `scope` (e.g. one tenant) when the caller supplied one.
         let rag_batch = self
             .data_plane
-            .rag_candidates(&collection, &query_embedding, 128)
+            .rag_candidates(&collection, &query_embedding, 128, scope.as_ref())
             .await;
 
         let (context, augmented_prompt) = match rag_batch {
             Ok(batch) => {
-                let candidates: Vec<CandidateChunk> = match record_batch_to_candidate_chunks(
-                    &batch,
-                    "id",
-                    "tokens",
-                    "embedding",
-                    &query_embedding,
-                ) {
-                    Ok(c) => c,
-                    Err(e) => {
-                        eprintln!("context build error: {e:?}");
-                        Vec::new()
-                    }
+                let mut dense_owned: Option<Vec<OwnedCandidateChunk>> = None;
+                let dense_candidates: Vec<CandidateChunk> =
+                    build_candidates(&batch, prompt, &query_embedding, &self.embedder, &mut dense_owned).await;
+
+                // Hybrid retrieval: fuse the dense ranking above with a lexical/BM25 ranking via
+                // Reciprocal Rank Fusion, so exact-term queries an embedding misses still
+                // surface. Gracefully degrades to pure vector search when the data plane has no
+                // lexical backend (the default `keyword_candidates` reports "unsupported").
+                let keyword_batch = self
+                    .data_plane
+                    .keyword_candidates(&collection, prompt, 128, scope.as_ref())
+                    .await;
+                let mut keyword_owned: Option<Vec<OwnedCandidateChunk>> = None;
+                let candidates: Vec<CandidateChunk> = if let Ok(kw_batch) = &keyword_batch {
+                    let keyword_candidates =
+                        build_candidates(kw_batch, prompt, &query_embedding, &self.embedder, &mut keyword_owned).await;
+                    const RRF_K: f32 = 60.0;
+                    let top_n = dense_candidates.len().max(keyword_candidates.len());
+                    fuse_candidates_rrf(&[dense_candidates, keyword_candidates], RRF_K, top_n)
+                } else {
+                    dense_candidates
                 };
 
                 let selection = self
@@ -164,19 +313,20 @@ impl GoniKernel {
                     .select(&query_embedding, &candidates, 2048)
                     .await;
 
-                // Append selected context text to prompt if available.
+                // Append selected context text to prompt if available, citing exact
+                // file:start-end provenance when the chunk came from `goni-chunker`.
                 let mut ctx_block = String::new();
                 for idx in &selection.indices {
                     if let Some(chunk) = candidates.get(*idx as usize) {
-                        if let Some(text) = chunk.text {
-                            ctx_block.push_str("- ");
-                            ctx_block.push_str(text);
-                            ctx_block.push('\n');
-                        } else {
-                            ctx_block.push_str("- ");
-                            ctx_block.push_str(chunk.id);
-                            ctx_block.push('\n');
-                        }
+                        let label = match (chunk.source_path, chunk.start_byte, chunk.end_byte) {
+                            (Some(path), Some(start), Some(end)) => format!("{path}:{start}-{end}"),
+                            _ => chunk.id.to_string(),
+                        };
+                        ctx_block.push_str("- [");
+                        ctx_block.push_str(&label);
+                        ctx_block.push_str("] ");
+                        ctx_block.push_str(chunk.text.unwrap_or(chunk.id));
+                        ctx_block.push('\n');
                     }
                 }
 
@@ -195,13 +345,22 @@ impl GoniKernel {
                     .unwrap_or(false);
                 if demo {
                     let demo_text = "demo context";
-                    let demo_emb = goni_embed::embed(demo_text, emb_dim);
+                    let demo_emb = self
+                        .embedder
+                        .embed_batch(&[demo_text])
+                        .await?
+                        .into_iter()
+                        .next()
+                        .unwrap_or_default();
                     let candidates = vec![CandidateChunk {
                         id: "demo",
                         text: Some(demo_text),
                         tokens: 1,
                         embedding: &demo_emb,
                         relevance: 1.0,
+                        source_path: None,
+                        start_byte: None,
+                        end_byte: None,
                     }];
                     let selection = self
                         .context_selector
@@ -221,17 +380,286 @@ impl GoniKernel {
             }
         };
 
-        let (routing, _policy): (goni_router::RoutingDecision, EscalationPolicy) =
+        let (routing, policy): (goni_router::RoutingDecision, EscalationPolicy) =
             self.router.decide(&augmented_prompt, &context).await;
 
         let req = LlmRequest {
+            request_id: Uuid::new_v4(),
             prompt: augmented_prompt,
             context,
             model_tier: routing.chosen_tier,
             max_tokens: 512,
         };
 
-        let stream = self.llm_engine.generate(req).await?;
-        Ok(stream)
+        let escalation = EscalationHandle::default();
+        let stream = match policy {
+            EscalationPolicy::None => self.llm_engine.generate(req).await?,
+            EscalationPolicy::SprtThreshold(threshold) => {
+                self.generate_with_escalation(req, threshold, escalation.clone())
+                    .await?
+            }
+        };
+
+        let stream = self.report_completion_on_drain(stream, meta);
+
+        Ok(SolveOutcome { stream, escalation })
+    }
+
+    /// Wraps `inner` so that once it's fully drained (or errors out), the kernel reports the
+    /// request's actual service time — tokens emitted over `meta.dequeue_ts.elapsed()` — to the
+    /// scheduler via [`Scheduler::report_complete`]. This lives here rather than at the HTTP layer
+    /// because only the kernel knows `meta`; callers just see a normal [`TokenStream`].
+    fn report_completion_on_drain(&self, inner: TokenStream, meta: BatchMeta) -> TokenStream {
+        let scheduler = self.scheduler.clone();
+        let out = stream::unfold(
+            (inner, scheduler, meta, 0usize),
+            |(mut inner, scheduler, meta, tokens_emitted)| async move {
+                match inner.next().await {
+                    Some(item) => {
+                        let tokens_emitted = tokens_emitted + usize::from(item.is_ok());
+                        Some((item, (inner, scheduler, meta, tokens_emitted)))
+                    }
+                    None => {
+                        if let Some(dequeue_ts) = meta.dequeue_ts {
+                            scheduler
+                                .report_complete(meta.id, meta.class, tokens_emitted, dequeue_ts.elapsed())
+                                .await;
+                        }
+                        None
+                    }
+                }
+            },
+        );
+
+        Box::pin(out) as TokenStream
     }
+
+    /// Streams `req` from its own `model_tier`, running a [`SprtState`] over each token's
+    /// log-prob. As soon as the test decides [`SprtDecision::Escalate`], the in-flight stream is
+    /// torn down and the prompt — with everything generated so far appended as a prefix — is
+    /// re-dispatched to [`next_tier`]; tokens already yielded to the caller are not replayed, so
+    /// the caller sees one continuous stream across the tier switch. `escalation` is updated the
+    /// moment that happens so receipt-writing callers can record it.
+    async fn generate_with_escalation(
+        &self,
+        req: LlmRequest,
+        threshold: f32,
+        escalation: EscalationHandle,
+    ) -> anyhow::Result<TokenStream> {
+        enum State {
+            Local {
+                inner: TokenStream,
+                sprt: SprtState,
+                prefix: String,
+                request: LlmRequest,
+            },
+            Escalated {
+                inner: TokenStream,
+            },
+        }
+
+        let inner = self.llm_engine.generate(req.clone()).await?;
+        let state = State::Local {
+            inner,
+            sprt: SprtState::new(threshold, SPRT_MAX_OBSERVATIONS),
+            prefix: String::new(),
+            request: req,
+        };
+        let engine = self.llm_engine.clone();
+
+        let out = stream::unfold((state, engine, escalation), |(state, engine, escalation)| async move {
+            match state {
+                State::Local {
+                    mut inner,
+                    mut sprt,
+                    mut prefix,
+                    request,
+                } => match inner.next().await {
+                    Some(Ok(tok)) => {
+                        prefix.push_str(&tok.text);
+                        let delta = log_likelihood_ratio_delta(tok.logprob);
+                        match sprt.observe(delta) {
+                            Some(SprtDecision::Escalate) => {
+                                let next_state = match next_tier(request.model_tier) {
+                                    None => State::Local {
+                                        inner,
+                                        sprt,
+                                        prefix,
+                                        request,
+                                    },
+                                    Some(next_tier) => {
+                                        escalation.set(next_tier);
+                                        let resumed = LlmRequest {
+                                            request_id: request.request_id,
+                                            prompt: format!("{}{}", request.prompt, prefix),
+                                            context: request.context.clone(),
+                                            model_tier: next_tier,
+                                            max_tokens: request.max_tokens,
+                                        };
+                                        match engine.generate(resumed).await {
+                                            Ok(tail) => {
+                                                escalation.set(next_tier);
+                                                State::Escalated { inner: tail }
+                                            }
+                                            Err(_) => State::Local {
+                                                inner,
+                                                sprt,
+                                                prefix,
+                                                request,
+                                            },
+                                        }
+                                    }
+                                };
+                                Some((Ok(tok) as Result<LlmToken, goni_infer::LlmError>, (next_state, engine, escalation)))
+                            }
+                            Some(SprtDecision::ContinueLocal) | None => Some((
+                                Ok(tok),
+                                (
+                                    State::Local {
+                                        inner,
+                                        sprt,
+                                        prefix,
+                                        request,
+                                    },
+                                    engine,
+                                    escalation,
+                                ),
+                            )),
+                        }
+                    }
+                    Some(Err(e)) => Some((
+                        Err(e),
+                        (
+                            State::Local {
+                                inner,
+                                sprt,
+                                prefix,
+                                request,
+                            },
+                            engine,
+                            escalation,
+                        ),
+                    )),
+                    None => None,
+                },
+                State::Escalated { mut inner } => {
+                    let item = inner.next().await?;
+                    Some((item, (State::Escalated { inner }, engine, escalation)))
+                }
+            }
+        });
+
+        Ok(Box::pin(out) as TokenStream)
+    }
+}
+
+/// Builds candidate chunks from a `rag_candidates`/`keyword_candidates` batch, picking the
+/// converter that matches the batch's actual `embedding` column instead of always assuming a
+/// plain `FixedSizeList<Float32>`:
+/// - zero-copy [`record_batch_to_candidate_chunks`] when the column is present and `Float32`;
+/// - [`record_batch_to_candidate_chunks_quantized`] when it's `Int8`/`UInt8`, dequantized via
+///   `EMBED_QUANT_SCALE`/`EMBED_QUANT_ZERO_POINT` (global scalar quantization; unset means no
+///   backend in this deployment produces quantized batches, so they're reported rather than
+///   guessed at);
+/// - [`record_batch_to_candidate_chunks_auto_embed`] when there's no `embedding` column at all
+///   (e.g. a keyword-only index), embedding `text` on the fly via `embedder`.
+///
+/// The owned conversions can't hand back zero-copy borrows of `batch`, so their chunks are
+/// stashed in `owned_buf` and then borrowed back out via [`OwnedCandidateChunk::as_candidate_chunk`]
+/// so callers keep working with a single `Vec<CandidateChunk>` either way.
+async fn build_candidates<'a>(
+    batch: &'a arrow::record_batch::RecordBatch,
+    query_text: &str,
+    query_embedding: &[f32],
+    embedder: &Arc<dyn Embedder>,
+    owned_buf: &'a mut Option<Vec<OwnedCandidateChunk>>,
+) -> Vec<CandidateChunk<'a>> {
+    let embedding_field = batch.schema().column_with_name("embedding").map(|(_, f)| f.data_type().clone());
+    match embedding_field {
+        None => match record_batch_to_candidate_chunks_auto_embed(
+            batch,
+            "id",
+            "tokens",
+            "text",
+            query_text,
+            embedder.as_ref(),
+        )
+        .await
+        {
+            Ok((_query_embedding, owned)) => {
+                *owned_buf = Some(owned);
+                owned_buf.as_ref().unwrap().iter().map(|c| c.as_candidate_chunk()).collect()
+            }
+            Err(e) => {
+                eprintln!("auto-embed candidate build error: {e:?}");
+                Vec::new()
+            }
+        },
+        Some(arrow::datatypes::DataType::FixedSizeList(inner, _))
+            if matches!(inner.data_type(), arrow::datatypes::DataType::Int8 | arrow::datatypes::DataType::UInt8) =>
+        {
+            let Ok(scale) = std::env::var("EMBED_QUANT_SCALE").unwrap_or_default().parse::<f32>() else {
+                eprintln!("embedding column is quantized but EMBED_QUANT_SCALE is unset/invalid");
+                return Vec::new();
+            };
+            let zero_point = std::env::var("EMBED_QUANT_ZERO_POINT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0);
+            match record_batch_to_candidate_chunks_quantized(
+                batch,
+                "id",
+                "tokens",
+                "embedding",
+                query_embedding,
+                &Quantization::Global { scale, zero_point },
+            ) {
+                Ok(owned) => {
+                    *owned_buf = Some(owned);
+                    owned_buf.as_ref().unwrap().iter().map(|c| c.as_candidate_chunk()).collect()
+                }
+                Err(e) => {
+                    eprintln!("quantized candidate build error: {e:?}");
+                    Vec::new()
+                }
+            }
+        }
+        Some(_) => match record_batch_to_candidate_chunks(batch, "id", "tokens", "embedding", query_embedding) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("context build error: {e:?}");
+                Vec::new()
+            }
+        },
+    }
+}
+
+/// Fuse ranked candidate lists with Reciprocal Rank Fusion:
+/// `score(id) = Σ_list 1/(k + rank_in_list(id))`, rank starting at 1, absence from a list
+/// contributing nothing. Needs no score calibration between lists since only ranks are used.
+/// Returns the top `top_n` candidates by descending fused score, deduped by id (first list wins
+/// ties on which chunk's text/embedding is kept).
+fn fuse_candidates_rrf<'a>(
+    lists: &[Vec<CandidateChunk<'a>>],
+    k: f32,
+    top_n: usize,
+) -> Vec<CandidateChunk<'a>> {
+    let mut scores: HashMap<&'a str, f32> = HashMap::new();
+    let mut by_id: HashMap<&'a str, CandidateChunk<'a>> = HashMap::new();
+
+    for list in lists {
+        for (rank, chunk) in list.iter().enumerate() {
+            *scores.entry(chunk.id).or_insert(0.0) += 1.0 / (k + (rank + 1) as f32);
+            by_id.entry(chunk.id).or_insert_with(|| chunk.clone());
+        }
+    }
+
+    let mut ranked: Vec<(&str, f32)> = scores.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    ranked
+        .into_iter()
+        .take(top_n)
+        .filter_map(|(id, _)| by_id.remove(id))
+        .collect()
 }
@@ -0,0 +1,127 @@
+//! Sequential Probability Ratio Test driving mid-generation tier escalation.
+//!
+//! [`SprtState`] accumulates a log-likelihood ratio Λ = Σ log(p₁(xᵢ)/p₀(xᵢ)) over a per-token
+//! quality signal, where H₀ is "the current tier is adequate" and H₁ is "generation needs
+//! escalation". `SprtThreshold(x)` parameterizes both error rates with the same `x` (α = β = x):
+//! escalate once Λ crosses the upper boundary B = log((1-β)/α); stay put while Λ is below the
+//! lower boundary A = log(β/(1-α)); keep sampling in between. A hard cap on observations forces
+//! a decision instead of deferring forever.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SprtDecision {
+    ContinueLocal,
+    Escalate,
+}
+
+#[derive(Debug, Clone)]
+pub struct SprtState {
+    alpha: f32,
+    beta: f32,
+    log_likelihood_ratio: f32,
+    observations: usize,
+    max_observations: usize,
+}
+
+impl SprtState {
+    /// `threshold` is `SprtThreshold`'s value, used as both α and β; it's clamped away from 0
+    /// and 0.5 so the boundaries stay finite. `max_observations` forces a decision (by the sign
+    /// of Λ) once reached, so a borderline stream can't defer forever.
+    pub fn new(threshold: f32, max_observations: usize) -> Self {
+        let alpha = threshold.clamp(1e-3, 0.499);
+        Self {
+            alpha,
+            beta: alpha,
+            log_likelihood_ratio: 0.0,
+            observations: 0,
+            max_observations,
+        }
+    }
+
+    fn upper_boundary(&self) -> f32 {
+        ((1.0 - self.beta) / self.alpha).ln()
+    }
+
+    fn lower_boundary(&self) -> f32 {
+        (self.beta / (1.0 - self.alpha)).ln()
+    }
+
+    /// Folds one token's log-likelihood-ratio contribution into Λ and returns the test's
+    /// decision, if it has reached one (by boundary crossing or by hitting `max_observations`).
+    pub fn observe(&mut self, log_likelihood_ratio_delta: f32) -> Option<SprtDecision> {
+        self.log_likelihood_ratio += log_likelihood_ratio_delta;
+        self.observations += 1;
+
+        if self.log_likelihood_ratio >= self.upper_boundary() {
+            return Some(SprtDecision::Escalate);
+        }
+        if self.log_likelihood_ratio <= self.lower_boundary() {
+            return Some(SprtDecision::ContinueLocal);
+        }
+        if self.observations >= self.max_observations {
+            return Some(if self.log_likelihood_ratio > 0.0 {
+                SprtDecision::Escalate
+            } else {
+                SprtDecision::ContinueLocal
+            });
+        }
+        None
+    }
+}
+
+/// Converts a token's log-prob into Λ's per-token increment: a token the model was unconfident
+/// about (very negative log-prob) is evidence for H1 ("needs escalation"); a confident one is
+/// evidence for H0. Tokens with no log-prob (engines that don't expose one) contribute no
+/// evidence either way, so escalation never fires on pure silence.
+const CONFIDENT_LOGPROB_BASELINE: f32 = -1.0;
+
+pub fn log_likelihood_ratio_delta(logprob: Option<f32>) -> f32 {
+    match logprob {
+        Some(lp) => CONFIDENT_LOGPROB_BASELINE - lp,
+        None => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confident_tokens_stay_local() {
+        let mut sprt = SprtState::new(0.1, 100);
+        let mut decision = None;
+        for _ in 0..20 {
+            decision = sprt.observe(log_likelihood_ratio_delta(Some(-0.01)));
+            if decision.is_some() {
+                break;
+            }
+        }
+        assert_eq!(decision, Some(SprtDecision::ContinueLocal));
+    }
+
+    #[test]
+    fn unconfident_tokens_escalate() {
+        let mut sprt = SprtState::new(0.1, 100);
+        let mut decision = None;
+        for _ in 0..20 {
+            decision = sprt.observe(log_likelihood_ratio_delta(Some(-5.0)));
+            if decision.is_some() {
+                break;
+            }
+        }
+        assert_eq!(decision, Some(SprtDecision::Escalate));
+    }
+
+    #[test]
+    fn forced_decision_at_observation_cap() {
+        let mut sprt = SprtState::new(0.3, 3);
+        // Mildly unconfident signal that never crosses a boundary on its own within the cap.
+        assert_eq!(sprt.observe(0.05), None);
+        assert_eq!(sprt.observe(0.05), None);
+        assert_eq!(sprt.observe(0.05), Some(SprtDecision::Escalate));
+    }
+
+    #[test]
+    fn missing_logprob_contributes_no_evidence() {
+        assert_eq!(log_likelihood_ratio_delta(None), 0.0);
+    }
+}
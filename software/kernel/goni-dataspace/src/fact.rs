@@ -0,0 +1,95 @@
+//! Arrow-row facts asserted into a [`crate::Dataspace`].
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use arrow::array::{BooleanArray, Int64Array, StringArray};
+use arrow::record_batch::RecordBatch;
+
+/// A scalar value extracted from one column of an asserted row, used both to store a fact's
+/// field values and to match a [`crate::Pattern`]'s exact-value constraints against them.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FieldValue {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+}
+
+/// One row of a `define_tables!` table, tagged with the Spine `kind`/`plane` it came from and
+/// keyed by column name, the way `define_tables!` itself keys a row's fields.
+#[derive(Clone, Debug)]
+pub struct Fact {
+    pub kind: String,
+    pub plane: String,
+    pub fields: BTreeMap<String, FieldValue>,
+    /// Kept around so an observer can read columns the matcher doesn't index (e.g. payload
+    /// blobs), not just the fields used as match constraints.
+    pub row: Arc<RecordBatch>,
+}
+
+impl Fact {
+    /// Builds a fact from one row of a [`define_tables`](goni_schema)-generated batch, downcasting
+    /// each column to the scalar types facts support ([`FieldValue`]) and silently skipping
+    /// columns whose Arrow type isn't one of those (e.g. `FixedSizeBinary`, dictionary-encoded
+    /// columns) — those columns just aren't matchable/bindable today, not an error.
+    pub fn from_row(
+        kind: impl Into<String>,
+        plane: impl Into<String>,
+        batch: &Arc<RecordBatch>,
+        row_idx: usize,
+    ) -> Self {
+        let mut fields = BTreeMap::new();
+        for (col_idx, field) in batch.schema().fields().iter().enumerate() {
+            let column = batch.column(col_idx);
+            let value = if let Some(arr) = column.as_any().downcast_ref::<StringArray>() {
+                (!arr.is_null(row_idx)).then(|| FieldValue::Str(arr.value(row_idx).to_string()))
+            } else if let Some(arr) = column.as_any().downcast_ref::<Int64Array>() {
+                (!arr.is_null(row_idx)).then(|| FieldValue::Int(arr.value(row_idx)))
+            } else if let Some(arr) = column.as_any().downcast_ref::<BooleanArray>() {
+                (!arr.is_null(row_idx)).then(|| FieldValue::Bool(arr.value(row_idx)))
+            } else {
+                None
+            };
+            if let Some(value) = value {
+                fields.insert(field.name().clone(), value);
+            }
+        }
+        Self {
+            kind: kind.into(),
+            plane: plane.into(),
+            fields,
+            row: batch.clone(),
+        }
+    }
+
+    /// Canonical bytes identifying this fact's content (kind + plane + sorted fields), used as
+    /// the refcounting key so re-asserting the same fact (e.g. a retry) is idempotent rather than
+    /// double-counted. Deliberately excludes `row`: two facts built from different underlying
+    /// batches but with identical field values are the same fact for refcounting purposes.
+    pub fn content_key(&self) -> Vec<u8> {
+        let mut key = Vec::new();
+        key.extend_from_slice(self.kind.as_bytes());
+        key.push(0);
+        key.extend_from_slice(self.plane.as_bytes());
+        for (field_name, value) in &self.fields {
+            key.push(0);
+            key.extend_from_slice(field_name.as_bytes());
+            key.push(b'=');
+            match value {
+                FieldValue::Str(s) => {
+                    key.push(b's');
+                    key.extend_from_slice(s.as_bytes());
+                }
+                FieldValue::Int(i) => {
+                    key.push(b'i');
+                    key.extend_from_slice(&i.to_le_bytes());
+                }
+                FieldValue::Bool(b) => {
+                    key.push(b'b');
+                    key.push(*b as u8);
+                }
+            }
+        }
+        key
+    }
+}
@@ -0,0 +1,18 @@
+//! Reactive dataspace over Arrow rows, modeled on Syndicate-style assertion/subscription
+//! semantics.
+//!
+//! Components assert [`Fact`]s — rows from `define_tables!` tables tagged with their Spine
+//! `kind`/`plane` — into a [`Dataspace`]; other components subscribe with a [`Pattern`] built
+//! from exact-value constraints, wildcards, and captures. Facts are reference-counted, so an
+//! [`Event::Assert`]/[`Event::Retract`] only fires on a fact's 0→1/1→0 count transition:
+//! re-asserting a fact that's already live (e.g. a retried write) is a no-op rather than a
+//! duplicate wake. Matching is indexed as a discrimination tree keyed by `(kind, field, value)`,
+//! so asserting a fact only visits observers that could plausibly match it.
+
+pub mod fact;
+pub mod matcher;
+pub mod pattern;
+
+pub use fact::{Fact, FieldValue};
+pub use matcher::{unify, Bindings, Dataspace, Event, ObserverId};
+pub use pattern::{FieldPattern, Pattern};
@@ -0,0 +1,277 @@
+//! Discrimination-tree-indexed matching and the reference-counted fact multiset itself.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::fact::{Fact, FieldValue};
+use crate::pattern::{FieldPattern, Pattern};
+
+pub type ObserverId = u64;
+
+/// Captured `field -> value` bindings produced by a successful [`unify`].
+pub type Bindings = HashMap<String, FieldValue>;
+
+/// A wake delivered to one matching observer when a fact's refcount crosses 0→1 (assert) or
+/// 1→0 (retract).
+#[derive(Clone, Debug)]
+pub enum Event {
+    Assert { observer: ObserverId, bindings: Bindings },
+    Retract { observer: ObserverId, bindings: Bindings },
+}
+
+/// Attempts to unify `pattern` against `fact`, returning the captured bindings on success.
+pub fn unify(pattern: &Pattern, fact: &Fact) -> Option<Bindings> {
+    if pattern.kind != fact.kind {
+        return None;
+    }
+    if let Some(plane) = &pattern.plane {
+        if plane != &fact.plane {
+            return None;
+        }
+    }
+    let mut bindings = Bindings::new();
+    for (field_name, field_pattern) in &pattern.fields {
+        let value = fact.fields.get(field_name)?;
+        match field_pattern {
+            FieldPattern::Exact(expected) => {
+                if value != expected {
+                    return None;
+                }
+            }
+            FieldPattern::Wildcard => {}
+            FieldPattern::Capture(name) => {
+                bindings.insert(name.clone(), value.clone());
+            }
+        }
+    }
+    Some(bindings)
+}
+
+struct Observer {
+    pattern: Pattern,
+}
+
+#[derive(Default)]
+struct Inner {
+    observers: HashMap<ObserverId, Observer>,
+    /// Discrimination tree: observers with at least one exact field constraint are indexed by
+    /// `(kind, field_name, value)`, so asserting a fact only visits observers that could
+    /// plausibly match it instead of every observer in the dataspace.
+    index: HashMap<(String, String, FieldValue), HashSet<ObserverId>>,
+    /// Observers with no exact constraint (wildcards/captures only) can't be narrowed by value,
+    /// so they're indexed by `kind` alone and always visited for a fact of that kind.
+    catch_all: HashMap<String, HashSet<ObserverId>>,
+    /// Refcount + a copy of the fact per content key, so re-asserting an already-live fact (or
+    /// retracting one still referenced elsewhere) doesn't spuriously re-fire observers.
+    counts: HashMap<Vec<u8>, (u32, Fact)>,
+}
+
+fn exact_constraint(pattern: &Pattern) -> Option<(String, FieldValue)> {
+    pattern.fields.iter().find_map(|(name, fp)| match fp {
+        FieldPattern::Exact(v) => Some((name.clone(), v.clone())),
+        _ => None,
+    })
+}
+
+fn dispatch(inner: &Inner, fact: &Fact, retracted: bool) -> Vec<Event> {
+    let mut candidates: HashSet<ObserverId> = HashSet::new();
+    if let Some(set) = inner.catch_all.get(&fact.kind) {
+        candidates.extend(set);
+    }
+    for (field_name, value) in &fact.fields {
+        let key = (fact.kind.clone(), field_name.clone(), value.clone());
+        if let Some(set) = inner.index.get(&key) {
+            candidates.extend(set);
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter_map(|observer| {
+            let bindings = unify(&inner.observers.get(&observer)?.pattern, fact)?;
+            Some(if retracted {
+                Event::Retract { observer, bindings }
+            } else {
+                Event::Assert { observer, bindings }
+            })
+        })
+        .collect()
+}
+
+/// Holds the live reference-counted multiset of asserted facts and the observers subscribed
+/// against them, dispatching [`Event::Assert`]/[`Event::Retract`] only on a fact's 0→1/1→0
+/// refcount transitions so duplicate assertions of the same fact are idempotent.
+pub struct Dataspace {
+    inner: Mutex<Inner>,
+    next_observer_id: AtomicU64,
+}
+
+impl Dataspace {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner::default()),
+            next_observer_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Registers `pattern` and returns its [`ObserverId`] for later [`Dataspace::unsubscribe`].
+    pub fn subscribe(&self, pattern: Pattern) -> ObserverId {
+        let id = self.next_observer_id.fetch_add(1, Ordering::Relaxed);
+        let mut inner = self.inner.lock().unwrap();
+
+        match exact_constraint(&pattern) {
+            Some((field_name, value)) => {
+                inner
+                    .index
+                    .entry((pattern.kind.clone(), field_name, value))
+                    .or_default()
+                    .insert(id);
+            }
+            None => {
+                inner.catch_all.entry(pattern.kind.clone()).or_default().insert(id);
+            }
+        }
+        inner.observers.insert(id, Observer { pattern });
+        id
+    }
+
+    pub fn unsubscribe(&self, observer: ObserverId) {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(obs) = inner.observers.remove(&observer) else { return };
+        match exact_constraint(&obs.pattern) {
+            Some((field_name, value)) => {
+                if let Some(set) = inner.index.get_mut(&(obs.pattern.kind.clone(), field_name, value)) {
+                    set.remove(&observer);
+                }
+            }
+            None => {
+                if let Some(set) = inner.catch_all.get_mut(&obs.pattern.kind) {
+                    set.remove(&observer);
+                }
+            }
+        }
+    }
+
+    /// Asserts `fact`, firing `Event::Assert` for every matching observer only if this is the
+    /// fact's first live reference (refcount 0→1). Re-asserting an already-live fact just bumps
+    /// the refcount silently.
+    pub fn assert(&self, fact: Fact) -> Vec<Event> {
+        let key = fact.content_key();
+        let mut inner = self.inner.lock().unwrap();
+        let entry = inner.counts.entry(key).or_insert_with(|| (0, fact.clone()));
+        entry.0 += 1;
+        if entry.0 != 1 {
+            return Vec::new();
+        }
+        dispatch(&inner, &fact, false)
+    }
+
+    /// Retracts one reference to the fact matching `fact.content_key()`, firing `Event::Retract`
+    /// for every matching observer only once the refcount transitions 1→0. A no-op if the fact
+    /// has no live references.
+    pub fn retract(&self, fact: &Fact) -> Vec<Event> {
+        let key = fact.content_key();
+        let mut inner = self.inner.lock().unwrap();
+        let Some(entry) = inner.counts.get_mut(&key) else {
+            return Vec::new();
+        };
+        entry.0 = entry.0.saturating_sub(1);
+        if entry.0 != 0 {
+            return Vec::new();
+        }
+        inner.counts.remove(&key);
+        dispatch(&inner, fact, true)
+    }
+}
+
+impl Default for Dataspace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::record_batch::RecordBatch;
+
+    fn tasks_fact(queue_id: &str, state: &str) -> Fact {
+        let mut fields = std::collections::BTreeMap::new();
+        fields.insert("queue_id".to_string(), FieldValue::Str(queue_id.to_string()));
+        fields.insert("state".to_string(), FieldValue::Str(state.to_string()));
+        Fact {
+            kind: "Tasks".to_string(),
+            plane: "Control".to_string(),
+            fields,
+            row: std::sync::Arc::new(RecordBatch::new_empty(std::sync::Arc::new(arrow::datatypes::Schema::empty()))),
+        }
+    }
+
+    #[test]
+    fn unify_captures_and_checks_exact_field() {
+        let pattern = Pattern::new("Tasks")
+            .exact("state", FieldValue::Str("Queued".to_string()))
+            .capture("queue_id", "queue");
+        let fact = tasks_fact("interactive", "Queued");
+        let bindings = unify(&pattern, &fact).expect("should unify");
+        assert_eq!(bindings.get("queue"), Some(&FieldValue::Str("interactive".to_string())));
+    }
+
+    #[test]
+    fn unify_rejects_exact_field_mismatch() {
+        let pattern = Pattern::new("Tasks").exact("state", FieldValue::Str("Queued".to_string()));
+        let fact = tasks_fact("interactive", "Running");
+        assert!(unify(&pattern, &fact).is_none());
+    }
+
+    #[test]
+    fn assert_wakes_matching_observer() {
+        let ds = Dataspace::new();
+        let observer = ds.subscribe(Pattern::new("Tasks").exact("state", FieldValue::Str("Queued".to_string())));
+
+        let events = ds.assert(tasks_fact("interactive", "Queued"));
+        assert_eq!(events.len(), 1);
+        assert!(matches!(&events[0], Event::Assert { observer: o, .. } if *o == observer));
+
+        let no_events = ds.assert(tasks_fact("interactive", "Running"));
+        assert!(no_events.is_empty());
+    }
+
+    #[test]
+    fn duplicate_assert_is_idempotent() {
+        let ds = Dataspace::new();
+        ds.subscribe(Pattern::new("Tasks").exact("state", FieldValue::Str("Queued".to_string())));
+
+        let first = ds.assert(tasks_fact("interactive", "Queued"));
+        assert_eq!(first.len(), 1);
+        let second = ds.assert(tasks_fact("interactive", "Queued"));
+        assert!(second.is_empty(), "re-asserting a live fact should not re-fire observers");
+    }
+
+    #[test]
+    fn retract_fires_only_on_last_reference() {
+        let ds = Dataspace::new();
+        ds.subscribe(Pattern::new("Tasks").exact("state", FieldValue::Str("Queued".to_string())));
+        let fact = tasks_fact("interactive", "Queued");
+
+        ds.assert(fact.clone());
+        ds.assert(fact.clone());
+        let first_retract = ds.retract(&fact);
+        assert!(first_retract.is_empty(), "fact still has one live reference");
+
+        let second_retract = ds.retract(&fact);
+        assert_eq!(second_retract.len(), 1);
+        assert!(matches!(&second_retract[0], Event::Retract { .. }));
+    }
+
+    #[test]
+    fn unsubscribe_stops_future_wakes() {
+        let ds = Dataspace::new();
+        let observer = ds.subscribe(Pattern::new("Tasks").exact("state", FieldValue::Str("Queued".to_string())));
+        ds.unsubscribe(observer);
+
+        let events = ds.assert(tasks_fact("interactive", "Queued"));
+        assert!(events.is_empty());
+    }
+}
@@ -0,0 +1,53 @@
+//! Patterns matched against [`crate::Fact`]s asserted into a [`crate::Dataspace`].
+
+use std::collections::BTreeMap;
+
+use crate::fact::FieldValue;
+
+/// One field constraint in a [`Pattern`]: match an exact value, match (and ignore) any value, or
+/// match (and bind) any value under a capture name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldPattern {
+    Exact(FieldValue),
+    Wildcard,
+    Capture(String),
+}
+
+/// Subscribes to facts of a given `kind` (and, optionally, `plane`) whose fields satisfy every
+/// listed constraint; fields the pattern doesn't mention are unconstrained.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    pub kind: String,
+    pub plane: Option<String>,
+    pub fields: BTreeMap<String, FieldPattern>,
+}
+
+impl Pattern {
+    pub fn new(kind: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            plane: None,
+            fields: BTreeMap::new(),
+        }
+    }
+
+    pub fn plane(mut self, plane: impl Into<String>) -> Self {
+        self.plane = Some(plane.into());
+        self
+    }
+
+    pub fn exact(mut self, field: impl Into<String>, value: FieldValue) -> Self {
+        self.fields.insert(field.into(), FieldPattern::Exact(value));
+        self
+    }
+
+    pub fn wildcard(mut self, field: impl Into<String>) -> Self {
+        self.fields.insert(field.into(), FieldPattern::Wildcard);
+        self
+    }
+
+    pub fn capture(mut self, field: impl Into<String>, name: impl Into<String>) -> Self {
+        self.fields.insert(field.into(), FieldPattern::Capture(name.into()));
+        self
+    }
+}
@@ -1,23 +1,52 @@
 use axum::{routing::{get, post}, Json, Router};
+use ed25519_dalek::VerifyingKey;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
-use goni_policy::{PolicyDecision, PolicyEngine};
+use goni_policy::{
+    CapabilityToken, ChallengeNonce, ChallengeProof, PolicyDecision, PolicyEngine, Privilege, ScopeGrant,
+};
 use goni_receipts::{Receipt, ReceiptLog};
 
 #[derive(Clone)]
 struct AppState {
     policy: Arc<PolicyEngine>,
     receipts: Arc<ReceiptLog>,
+    /// Public keys of trusted capability issuers, keyed by `issuer_key_id` (see
+    /// `CapabilityToken::issuer_key_id`); loaded once at startup from
+    /// `GONI_CAPABILITY_ISSUER_KEYS_FILE`.
+    issuer_keys: Arc<HashMap<String, VerifyingKey>>,
+    /// Nonces handed out by `/challenge`, keyed by `token_id`, consumed (removed) the first time
+    /// `/fetch` sees a matching `ChallengeProof` — this is what stops a captured proof from
+    /// being replayed against a later call.
+    pending_challenges: Arc<Mutex<HashMap<Uuid, ChallengeNonce>>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChallengeRequest {
+    token_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+struct ChallengeResponse {
+    nonce: ChallengeNonce,
 }
 
 #[derive(Debug, Deserialize)]
 struct FetchRequest {
     url: String,
     method: Option<String>,
-    capability_token: String,
+    /// The full signed capability, not an opaque bearer string: the gate verifies its
+    /// signature, expiry, and scope itself instead of trusting whatever the kernel handed out.
+    token: CapabilityToken,
+    /// Proof of possession of `token`: an Ed25519 signature over the nonce returned by
+    /// `/challenge`, made with the holder's own private key (see `token.holder_key` and
+    /// `goni_policy::compute_challenge_proof`) — never `token.signature`, which is resent in
+    /// plaintext on this very call and so provides no replay protection on its own.
+    proof: ChallengeProof,
 }
 
 #[derive(Debug, Serialize)]
@@ -28,11 +57,17 @@ struct FetchResponse {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // `GONI_EGRESS_ALLOWLIST` entries are host globs (e.g. `*.example.com`) granted full
+    // `Administer` privilege: operators who can edit this env var are already trusted to manage
+    // egress, so there's no finer-grained tier to express here yet.
     let allowlist = std::env::var("GONI_EGRESS_ALLOWLIST").unwrap_or_default();
     let hosts = allowlist
         .split(',')
         .filter(|s| !s.is_empty())
-        .map(|s| s.to_string())
+        .map(|s| ScopeGrant {
+            target: s.to_string(),
+            privilege: Privilege::Administer,
+        })
         .collect::<Vec<_>>();
 
     let policy = if hosts.is_empty() {
@@ -42,15 +77,34 @@ async fn main() -> anyhow::Result<()> {
     };
 
     let receipt_path = std::env::var("GONI_RECEIPTS_FILE").unwrap_or_else(|_| "./receipts.jsonl".into());
-    let receipts = ReceiptLog::open(receipt_path)?;
+    let (signing_key, key_id) =
+        goni_receipts::signing_key_from_env("GONI_RECEIPT_SIGNING_KEY", "GONI_RECEIPT_KEY_ID");
+    let receipt_cipher = goni_receipts::receipt_cipher_from_env("GONI_RECEIPT_DATA_KEY");
+    let receipts = ReceiptLog::with_backend(
+        Arc::new(goni_receipts::LocalFileBackend::new(receipt_path)),
+        signing_key,
+        key_id,
+        receipt_cipher,
+    )
+    .await?;
+
+    let issuer_keys_path =
+        std::env::var("GONI_CAPABILITY_ISSUER_KEYS_FILE").unwrap_or_else(|_| "./capability_issuer_keys.txt".into());
+    let issuer_keys = goni_receipts::load_verifying_keys(&issuer_keys_path).unwrap_or_else(|e| {
+        eprintln!("{issuer_keys_path} not readable ({e}); no capability tokens will verify");
+        HashMap::new()
+    });
 
     let state = AppState {
         policy: Arc::new(policy),
         receipts: Arc::new(receipts),
+        issuer_keys: Arc::new(issuer_keys),
+        pending_challenges: Arc::new(Mutex::new(HashMap::new())),
     };
 
     let app = Router::new()
         .route("/healthz", get(healthz))
+        .route("/challenge", post(challenge))
         .route("/fetch", post(fetch))
         .with_state(state);
 
@@ -64,12 +118,47 @@ async fn healthz() -> &'static str {
     "ok"
 }
 
+/// SASL-style first step: a client requests a fresh nonce for the token it intends to present to
+/// `/fetch`, so the proof it sends back can't be replayed from an earlier, captured exchange.
+async fn challenge(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Json(req): Json<ChallengeRequest>,
+) -> Json<ChallengeResponse> {
+    let nonce = ChallengeNonce::generate();
+    state
+        .pending_challenges
+        .lock()
+        .unwrap()
+        .insert(req.token_id, nonce.clone());
+    Json(ChallengeResponse { nonce })
+}
+
 async fn fetch(
     axum::extract::State(state): axum::extract::State<AppState>,
     Json(req): Json<FetchRequest>,
 ) -> Result<Json<FetchResponse>, (axum::http::StatusCode, String)> {
     let host = req.url.split('/').nth(2).unwrap_or("");
-    let decision = state.policy.evaluate_egress(host);
+
+    // A nonce is consumed on first use regardless of outcome: re-presenting the same proof
+    // (e.g. replayed by an observer) always lands on `no_pending_challenge` afterwards.
+    let nonce = state.pending_challenges.lock().unwrap().remove(&req.token.token_id);
+    let capability_check = match nonce {
+        None => PolicyDecision::Deny("no_pending_challenge".into()),
+        Some(nonce) => match state.policy.verify_challenge_proof(&req.token, &nonce, &req.proof) {
+            PolicyDecision::Allow => state.policy.verify_capability(
+                &req.token,
+                host,
+                &state.issuer_keys,
+                &chrono::Utc::now().to_rfc3339(),
+            ),
+            deny => deny,
+        },
+    };
+
+    let decision = match &capability_check {
+        PolicyDecision::Allow => state.policy.evaluate_egress(host, Privilege::Operate),
+        deny => deny.clone(),
+    };
 
     let receipt = Receipt {
         receipt_id: Uuid::new_v4(),
@@ -79,13 +168,19 @@ async fn fetch(
             PolicyDecision::Allow => "allow".into(),
             PolicyDecision::Deny(r) => format!("deny:{r}"),
         },
-        capability_id: Some(Uuid::new_v5(&Uuid::NAMESPACE_OID, req.capability_token.as_bytes())),
+        capability_id: Some(req.token.token_id),
+        capability_check: Some(match &capability_check {
+            PolicyDecision::Allow => "allow".into(),
+            PolicyDecision::Deny(r) => format!("deny:{r}"),
+        }),
         input_hash: "".into(),
         output_hash: "".into(),
         prev_hash: None,
         chain_hash: "".into(),
+        signer_key_id: String::new(),
+        signature: String::new(),
     };
-    let _ = state.receipts.append(receipt);
+    let _ = state.receipts.append(receipt).await;
 
     if !matches!(decision, PolicyDecision::Allow) {
         return Err((axum::http::StatusCode::FORBIDDEN, "egress denied".into()));
@@ -1,3 +1,4 @@
+use async_trait::async_trait;
 use sha2::{Digest, Sha256};
 
 pub fn embed(text: &str, dim: usize) -> Vec<f32> {
@@ -10,13 +11,180 @@ pub fn embed(text: &str, dim: usize) -> Vec<f32> {
         let sign = if digest[1] % 2 == 0 { 1.0 } else { -1.0 };
         v[idx] += sign;
     }
+    normalize(&mut v);
+    v
+}
+
+/// Scale `v` to unit length in place, leaving an all-zero vector untouched so downstream cosine
+/// similarity never divides by zero.
+fn normalize(v: &mut [f32]) {
     let norm: f32 = v.iter().map(|x| x * x).sum::<f32>().sqrt();
     if norm > 0.0 {
         for x in v.iter_mut() {
             *x /= norm;
         }
     }
-    v
+}
+
+/// Turns text into vectors. Every implementation must return unit-length vectors, so downstream
+/// cosine similarity reduces to a dot product.
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    async fn embed_batch(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>>;
+
+    /// Dimensionality of vectors this embedder returns.
+    fn dim(&self) -> usize;
+}
+
+/// Deterministic local embedder (hashed bag-of-tokens). No network dependency; ingestion and
+/// query always agree on `dim` because both go through the same instance.
+pub struct LexicalEmbedder {
+    dim: usize,
+}
+
+impl LexicalEmbedder {
+    pub fn new(dim: usize) -> Self {
+        Self { dim }
+    }
+}
+
+#[async_trait]
+impl Embedder for LexicalEmbedder {
+    async fn embed_batch(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|t| embed(t, self.dim)).collect())
+    }
+
+    fn dim(&self) -> usize {
+        self.dim
+    }
+}
+
+#[derive(serde::Serialize)]
+struct OpenAiEmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [&'a str],
+}
+
+#[derive(serde::Deserialize)]
+struct OpenAiEmbeddingResponse {
+    data: Vec<OpenAiEmbeddingItem>,
+}
+
+#[derive(serde::Deserialize)]
+struct OpenAiEmbeddingItem {
+    embedding: Vec<f32>,
+}
+
+/// Embedder backed by an OpenAI-compatible `/embeddings` endpoint (also served by vLLM and
+/// most local-inference gateways).
+pub struct OpenAiEmbedder {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    dim: usize,
+}
+
+impl OpenAiEmbedder {
+    pub fn new(base_url: impl Into<String>, model: impl Into<String>, dim: usize) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into(),
+            model: model.into(),
+            dim,
+        }
+    }
+}
+
+#[async_trait]
+impl Embedder for OpenAiEmbedder {
+    async fn embed_batch(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let url = format!("{}/embeddings", self.base_url);
+        let body = OpenAiEmbeddingRequest {
+            model: &self.model,
+            input: texts,
+        };
+        let resp = self.client.post(&url).json(&body).send().await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("openai embeddings status: {}", resp.status());
+        }
+        let parsed: OpenAiEmbeddingResponse = resp.json().await?;
+        Ok(parsed
+            .data
+            .into_iter()
+            .map(|mut item| {
+                normalize(&mut item.embedding);
+                item.embedding
+            })
+            .collect())
+    }
+
+    fn dim(&self) -> usize {
+        self.dim
+    }
+}
+
+#[derive(serde::Serialize)]
+struct OllamaEmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [&'a str],
+}
+
+#[derive(serde::Deserialize)]
+struct OllamaEmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// Embedder backed by Ollama's batched `/api/embed` endpoint.
+pub struct OllamaEmbedder {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    dim: usize,
+}
+
+impl OllamaEmbedder {
+    pub fn new(base_url: impl Into<String>, model: impl Into<String>, dim: usize) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into(),
+            model: model.into(),
+            dim,
+        }
+    }
+}
+
+#[async_trait]
+impl Embedder for OllamaEmbedder {
+    async fn embed_batch(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let url = format!("{}/api/embed", self.base_url);
+        let body = OllamaEmbedRequest {
+            model: &self.model,
+            input: texts,
+        };
+        let resp = self.client.post(&url).json(&body).send().await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("ollama embed status: {}", resp.status());
+        }
+        let parsed: OllamaEmbedResponse = resp.json().await?;
+        Ok(parsed
+            .embeddings
+            .into_iter()
+            .map(|mut v| {
+                normalize(&mut v);
+                v
+            })
+            .collect())
+    }
+
+    fn dim(&self) -> usize {
+        self.dim
+    }
 }
 
 #[cfg(test)]
@@ -29,4 +197,12 @@ mod tests {
         let b = embed("hello world", 16);
         assert_eq!(a, b);
     }
+
+    #[tokio::test]
+    async fn lexical_embedder_batches_match_single_calls() {
+        let embedder = LexicalEmbedder::new(32);
+        let batch = embedder.embed_batch(&["hello world", "goodbye"]).await.unwrap();
+        assert_eq!(batch[0], embed("hello world", 32));
+        assert_eq!(batch[1], embed("goodbye", 32));
+    }
 }
@@ -1,26 +1,35 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use axum::{extract::State, routing::{get, post}, Json, Router};
+use arrow::array::StringArray;
+use arrow::datatypes::{DataType, Field, Schema};
+use axum::{extract::{Query, State}, routing::{get, post}, Json, Router};
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use tower_http::trace::TraceLayer;
 use uuid::Uuid;
-use sha2::{Digest, Sha256};
 
+use goni_classifier::classify;
 use goni_core::GoniKernel;
-use goni_context::{FacilityLocationSelector, NullKvPager};
+use goni_context::{FacilityLocationSelector, KvPager, LruKvPager, NullKvPager};
+use goni_embed::{Embedder, LexicalEmbedder, OllamaEmbedder, OpenAiEmbedder};
 use goni_infer::{HttpVllmEngine, NullLlmEngine};
-use goni_receipts::{Receipt, ReceiptLog};
+use goni_receipts::{Receipt, ReceiptLog, SyncResult, SyncToken};
+use goni_redactor::{RedactionEngine, RedactionProfile};
 use goni_router::{ConfigRouter, NullRouter, Router};
 use goni_sched::InMemoryScheduler;
-use goni_store::{InMemorySpineDataPlane, MultiDataPlane, NullDataPlane, QdrantDataPlane};
+use goni_store::{
+    ArrowBatch, DataFusionDataPlane, InMemorySpineDataPlane, MultiDataPlane, NullDataPlane,
+    QdrantDataPlane, RagFilter,
+};
 use goni_types::TaskClass;
 
 #[derive(Clone)]
 struct AppState {
     kernel: Arc<GoniKernel>,
     receipts: Arc<ReceiptLog>,
+    /// Guards completion text before it leaves the process; see `chat_completions`.
+    redactor: Arc<RedactionEngine>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -31,6 +40,10 @@ struct ChatCompletionRequest {
     max_tokens: Option<u32>,
     #[serde(default)]
     stream: Option<bool>,
+    /// OpenAI-compatible end-user identifier; reused here as the `tenant` payload key to scope
+    /// `rag_candidates` when the collection is shared across tenants.
+    #[serde(default)]
+    user: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -73,20 +86,57 @@ async fn main() -> anyhow::Result<()> {
         );
     }
 
+    // Embedder: shared by ingestion (QdrantDataPlane) and query (GoniKernel::solve_prompt) so
+    // they always agree on dimension and semantic space.
+    let embed_dim: usize = std::env::var("EMBED_DIM")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1024);
+    let embedder: Arc<dyn Embedder> = match std::env::var("EMBED_BACKEND").as_deref() {
+        Ok("openai") => {
+            let url = std::env::var("EMBED_URL").unwrap_or_else(|_| "http://localhost:8000/v1".into());
+            let model = std::env::var("EMBED_MODEL").unwrap_or_else(|_| "text-embedding".into());
+            Arc::new(OpenAiEmbedder::new(url, model, embed_dim))
+        }
+        Ok("ollama") => {
+            let url = std::env::var("EMBED_URL").unwrap_or_else(|_| "http://localhost:11434".into());
+            let model = std::env::var("EMBED_MODEL").unwrap_or_else(|_| "nomic-embed-text".into());
+            Arc::new(OllamaEmbedder::new(url, model, embed_dim))
+        }
+        _ => Arc::new(LexicalEmbedder::new(embed_dim)),
+    };
+
     // Data plane:
     // - an in-memory "spine" for control/state/audit tables (append-only during MVP)
     // - an optional Qdrant backend for RAG search/ingest
-    let spine_plane: Arc<dyn goni_store::DataPlane> = Arc::new(InMemorySpineDataPlane::new());
+    // `GONI_SPINE_BACKEND=datafusion` opts into the schema-validating DataFusion spine; unset
+    // (the default) keeps the original in-memory spine, same behavior as before this plane existed.
+    let spine_plane: Arc<dyn goni_store::DataPlane> = match std::env::var("GONI_SPINE_BACKEND")
+        .as_deref()
+    {
+        Ok("datafusion") => Arc::new(DataFusionDataPlane::new()),
+        _ => Arc::new(InMemorySpineDataPlane::new()),
+    };
     let rag_plane: Arc<dyn goni_store::DataPlane> = match std::env::var("QDRANT_HTTP_URL") {
-        Ok(url) if !url.is_empty() => Arc::new(QdrantDataPlane::new(url)),
+        Ok(url) if !url.is_empty() => Arc::new(QdrantDataPlane::new(url, embedder.clone())),
         _ => Arc::new(NullDataPlane),
     };
     let data_plane: Arc<dyn goni_store::DataPlane> = Arc::new(MultiDataPlane::new(spine_plane, rag_plane));
     let context_selector = Arc::new(FacilityLocationSelector::new(0.3));
-    let kv_pager = Arc::new(NullKvPager);
+    // `GONI_KV_PAGER_BUDGET_PAGES` opts into real device-memory-budgeted eviction; unset (the
+    // default) keeps every page resident forever, same as before this pager existed.
+    let kv_pager: Arc<dyn KvPager> = match std::env::var("GONI_KV_PAGER_BUDGET_PAGES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+    {
+        Some(budget_pages) => Arc::new(LruKvPager::new(budget_pages)),
+        None => Arc::new(NullKvPager),
+    };
     let scheduler = Arc::new(InMemoryScheduler::new());
+    // `watch` (not `from_path`) so editing `council.yaml` updates routing thresholds without
+    // restarting the orchestrator; a malformed edit is logged and the previous config stays live.
     let router: Arc<dyn Router> = match std::env::var("GONI_ROUTER_CONFIG") {
-        Ok(path) => ConfigRouter::from_path(path)
+        Ok(path) => ConfigRouter::watch(path)
             .map(|r| Arc::new(r) as Arc<dyn Router>)
             .unwrap_or_else(|_| Arc::new(NullRouter)),
         Err(_) => Arc::new(NullRouter),
@@ -97,17 +147,26 @@ async fn main() -> anyhow::Result<()> {
     let llm_engine: Arc<dyn goni_infer::LlmEngine> = if use_stub {
         Arc::new(NullLlmEngine)
     } else {
-        Arc::new(HttpVllmEngine::new(
-            llm_url,
-            llm_model,
-            llm_deterministic,
-            llm_seed,
-        ))
+        Arc::new(
+            HttpVllmEngine::new(llm_url, llm_model, llm_deterministic, llm_seed)
+                .with_metrics_sink(data_plane.clone()),
+        )
     };
 
     let receipt_path = std::env::var("GONI_RECEIPTS_FILE")
         .unwrap_or_else(|_| "./receipts.jsonl".into());
-    let receipts = Arc::new(ReceiptLog::open(receipt_path)?);
+    let (signing_key, key_id) =
+        goni_receipts::signing_key_from_env("GONI_RECEIPT_SIGNING_KEY", "GONI_RECEIPT_KEY_ID");
+    let receipt_cipher = goni_receipts::receipt_cipher_from_env("GONI_RECEIPT_DATA_KEY");
+    let receipts = Arc::new(
+        ReceiptLog::with_backend(
+            Arc::new(goni_receipts::LocalFileBackend::new(receipt_path)),
+            signing_key,
+            key_id,
+            receipt_cipher,
+        )
+        .await?,
+    );
 
     let kernel = Arc::new(GoniKernel::new(
         data_plane,
@@ -116,6 +175,7 @@ async fn main() -> anyhow::Result<()> {
         scheduler,
         router,
         llm_engine,
+        embedder,
     ));
 
     // Start the scheduler executor loop (LLM-as-interrupt handler).
@@ -125,11 +185,13 @@ async fn main() -> anyhow::Result<()> {
         kernel_exec.run_scheduler_loop().await;
     });
 
-    let app_state = AppState { kernel, receipts };
+    let redactor = Arc::new(RedactionEngine::with_default_rules());
+    let app_state = AppState { kernel, receipts, redactor };
 
     let app = Router::new()
         .route("/healthz", get(healthz))
         .route("/v1/chat/completions", post(chat_completions))
+        .route("/v1/receipts", get(list_receipts))
         .with_state(app_state)
         .layer(TraceLayer::new_for_http());
 
@@ -156,12 +218,14 @@ async fn chat_completions(
     }
 
     let max_tokens = req.max_tokens.unwrap_or(256);
+    let scope = req.user.as_deref().map(|tenant| RagFilter::equals("tenant", tenant));
 
-    let mut stream = state
+    let outcome = state
         .kernel
-        .handle_user_query(&prompt, TaskClass::Interactive)
+        .handle_user_query(&prompt, TaskClass::Interactive, scope)
         .await
         .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let mut stream = outcome.stream;
 
     let mut full_text = String::new();
     while let Some(tok_res) = stream.next().await {
@@ -177,36 +241,140 @@ async fn chat_completions(
         }
     }
 
+    // Redact before the completion leaves the process: `fail_closed: false` here means a
+    // `Secret`-classified reply with no rule match still passes through (best-effort guard,
+    // not a hard block on generation) rather than turning a missed pattern into a 500.
+    let request_id = Uuid::new_v4();
+    let redaction_profile = RedactionProfile {
+        mode: "default".into(),
+        ruleset_hash: state.redactor.ruleset_hash(),
+        fail_closed: false,
+    };
+    let redaction = state
+        .redactor
+        .redact(&full_text, classify(&full_text), &redaction_profile)
+        .expect("fail_closed=false never returns Err");
+    record_redaction_event(state.kernel.data_plane.clone(), request_id, &redaction);
+
     let resp = ChatCompletionResponse {
-        id: Uuid::new_v4().to_string(),
+        id: request_id.to_string(),
         object: "chat.completion".into(),
         choices: vec![Choice {
             index: 0,
             message: Message {
                 role: "assistant".into(),
-                content: full_text,
+                content: redaction.output,
             },
         }],
     };
 
-    let input_hash = format!("{:x}", Sha256::digest(prompt.as_bytes()));
-    let output_hash = format!("{:x}", Sha256::digest(resp.choices[0].message.content.as_bytes()));
+    // Hashed via `canonical_hash` (not a raw `Sha256::digest` of the text) so these line up with
+    // `ToolCall::args_hash` and stay verifiable by non-Rust readers of the receipt chain.
+    let input_hash = hex::encode(goni_receipts::canonical_hash(&serde_json::Value::String(prompt.clone())));
+    let output_hash = hex::encode(goni_receipts::canonical_hash(&serde_json::Value::String(
+        resp.choices[0].message.content.clone(),
+    )));
+    // Record an SPRT-driven mid-generation tier escalation (see `GoniKernel::solve_prompt`) on
+    // the receipt, so auditing the chain shows which requests didn't stay on their routed tier.
+    let policy_decision = match outcome.escalation.get() {
+        Some(tier) => format!("allow:escalated_to={tier:?}"),
+        None => "allow".into(),
+    };
     let receipt = Receipt {
         receipt_id: Uuid::new_v4(),
         timestamp: chrono::Utc::now().to_rfc3339(),
         action_type: "chat.completion".into(),
-        policy_decision: "allow".into(),
+        policy_decision,
         capability_id: None,
+        capability_check: None,
         input_hash,
         output_hash,
         prev_hash: None,
         chain_hash: String::new(),
+        signer_key_id: String::new(),
+        signature: String::new(),
     };
-    let _ = state.receipts.append(receipt);
+    let _ = state.receipts.append(receipt).await;
 
     Ok(Json(resp))
 }
 
+#[derive(Debug, Deserialize)]
+struct ReceiptSyncQuery {
+    sync_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReceiptSyncResponse {
+    receipts: Vec<Receipt>,
+    sync_token: String,
+}
+
+/// CalDAV-style `sync-collection`: returns receipts appended after `sync_token` plus a new
+/// token to resume from, so audit consumers can tail the log cheaply instead of re-reading it
+/// in full every poll. Omitting `sync_token` returns the whole log. A stale or forked token
+/// yields `410 Gone` telling the caller to drop its cursor and re-sync from the start (by
+/// omitting `sync_token`); a token that doesn't decode yields `400 Bad Request`.
+async fn list_receipts(
+    State(state): State<AppState>,
+    Query(query): Query<ReceiptSyncQuery>,
+) -> Result<Json<ReceiptSyncResponse>, (axum::http::StatusCode, String)> {
+    let since = query
+        .sync_token
+        .as_deref()
+        .map(SyncToken::decode)
+        .transpose()
+        .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    match state
+        .receipts
+        .sync_page(since.as_ref())
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        SyncResult::Ok { receipts, next_token } => Ok(Json(ReceiptSyncResponse {
+            receipts,
+            sync_token: next_token.encode(),
+        })),
+        SyncResult::ResyncRequired => Err((
+            axum::http::StatusCode::GONE,
+            "sync_token invalid or stale; full resync required".into(),
+        )),
+    }
+}
+
+/// Build and fire off the `RedactionEvents` row for a completed redaction pass. Best-effort:
+/// failures are dropped, mirroring how `HttpVllmEngine` emits `LlmCalls` rows.
+fn record_redaction_event(
+    data_plane: Arc<dyn goni_store::DataPlane>,
+    request_id: Uuid,
+    redaction: &goni_redactor::RedactionOutcome,
+) {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("redaction_event_id", DataType::Utf8, false),
+        Field::new("request_id", DataType::Utf8, false),
+        Field::new("before_hash", DataType::Utf8, false),
+        Field::new("after_hash", DataType::Utf8, false),
+        Field::new("redaction_summary", DataType::Utf8, false),
+    ]));
+    let summary_json = serde_json::to_string(&redaction.redaction_summary).unwrap_or_default();
+    let batch = ArrowBatch::try_new(
+        schema,
+        vec![
+            Arc::new(StringArray::from(vec![Uuid::new_v4().to_string()])),
+            Arc::new(StringArray::from(vec![request_id.to_string()])),
+            Arc::new(StringArray::from(vec![hex::encode(redaction.before_hash)])),
+            Arc::new(StringArray::from(vec![hex::encode(redaction.after_hash)])),
+            Arc::new(StringArray::from(vec![summary_json])),
+        ],
+    );
+    if let Ok(batch) = batch {
+        tokio::spawn(async move {
+            let _ = data_plane.append_batches("RedactionEvents", vec![Arc::new(batch)]).await;
+        });
+    }
+}
+
 async fn healthz() -> &'static str {
     "ok"
 }
@@ -1,11 +1,15 @@
 use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
 
+use arrow::array::{BooleanArray, StringArray, UInt32Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
-use futures_util::{stream, StreamExt};
+use futures_util::stream;
 use goni_types::LlmRequest;
 use serde::{Deserialize, Serialize};
-
-type DynStream = Pin<Box<dyn futures_core::Stream<Item = Result<crate::LlmToken, crate::LlmError>> + Send>>;
+use uuid::Uuid;
 
 use crate::{LlmEngine, LlmError, LlmToken, TokenStream};
 
@@ -17,6 +21,17 @@ struct OpenAIChatRequest {
     stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     seed: Option<u64>,
+    stream_options: StreamOptions,
+    /// Requests per-token log-probs on the sampled token so `goni_core`'s SPRT escalation
+    /// policy has a quality signal to accumulate over.
+    logprobs: bool,
+}
+
+/// Ask vLLM to emit a terminal `usage` object once the stream completes, so we can record
+/// token counts in `LlmCalls` without a separate accounting call.
+#[derive(Serialize)]
+struct StreamOptions {
+    include_usage: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -27,7 +42,10 @@ struct OpenAIMessage {
 
 #[derive(Deserialize)]
 struct ChatCompletionChunk {
+    #[serde(default)]
     choices: Vec<ChatChoiceDelta>,
+    #[serde(default)]
+    usage: Option<Usage>,
 }
 
 #[derive(Deserialize)]
@@ -35,14 +53,36 @@ struct ChatChoiceDelta {
     delta: Delta,
     #[serde(default)]
     index: usize,
+    #[serde(default)]
+    finish_reason: Option<String>,
+    #[serde(default)]
+    logprobs: Option<ChoiceLogprobs>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Default)]
 struct Delta {
     #[serde(default)]
     content: String,
 }
 
+#[derive(Deserialize, Default)]
+struct ChoiceLogprobs {
+    #[serde(default)]
+    content: Vec<TokenLogprob>,
+}
+
+#[derive(Deserialize)]
+struct TokenLogprob {
+    logprob: f32,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+struct Usage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+    total_tokens: u32,
+}
+
 /// Simple HTTP LLM engine that calls a vLLM OpenAI-compatible endpoint.
 pub struct HttpVllmEngine {
     client: reqwest::Client,
@@ -50,6 +90,8 @@ pub struct HttpVllmEngine {
     model: String,
     deterministic: bool,
     seed: Option<u64>,
+    /// Optional sink for an `LlmCalls` row emitted when a stream finishes.
+    data_plane: Option<Arc<dyn goni_store::DataPlane>>,
 }
 
 impl HttpVllmEngine {
@@ -60,8 +102,132 @@ impl HttpVllmEngine {
             model,
             deterministic,
             seed,
+            data_plane: None,
+        }
+    }
+
+    /// Attach a data plane: completed streams will append a row to `LlmCalls` with token
+    /// counts and latency.
+    pub fn with_metrics_sink(mut self, data_plane: Arc<dyn goni_store::DataPlane>) -> Self {
+        self.data_plane = Some(data_plane);
+        self
+    }
+}
+
+/// Accumulates raw bytes across TCP chunk boundaries and only hands complete `\n\n`-delimited
+/// SSE frames to the JSON parser, so a `data:` line (or a multi-byte UTF-8 codepoint within
+/// one) split across two chunks is never corrupted.
+struct SseBuffer {
+    raw: Vec<u8>,
+}
+
+impl SseBuffer {
+    fn new() -> Self {
+        Self { raw: Vec::new() }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.raw.extend_from_slice(bytes);
+    }
+
+    /// Pop the next complete frame (without its trailing `\n\n`), if any.
+    fn next_frame(&mut self) -> Option<String> {
+        let pos = self
+            .raw
+            .windows(2)
+            .position(|w| w == b"\n\n")?;
+        let frame: Vec<u8> = self.raw.drain(..pos + 2).collect();
+        Some(String::from_utf8_lossy(&frame[..frame.len() - 2]).into_owned())
+    }
+}
+
+fn parse_frame(frame: &str, token_id: &mut u32) -> (Vec<Result<LlmToken, LlmError>>, Option<Usage>, bool) {
+    let mut tokens = Vec::new();
+    let mut usage = None;
+    let mut done = false;
+    for line in frame.lines() {
+        let line = line.trim();
+        let Some(payload) = line.strip_prefix("data:") else { continue };
+        let payload = payload.trim();
+        if payload.is_empty() {
+            continue;
+        }
+        if payload == "[DONE]" {
+            done = true;
+            continue;
+        }
+        match serde_json::from_str::<ChatCompletionChunk>(payload) {
+            Ok(chunk) => {
+                if chunk.usage.is_some() {
+                    usage = chunk.usage;
+                }
+                for choice in chunk.choices {
+                    if !choice.delta.content.is_empty() {
+                        let logprob = choice
+                            .logprobs
+                            .as_ref()
+                            .and_then(|lp| lp.content.first())
+                            .map(|t| t.logprob);
+                        tokens.push(Ok(LlmToken {
+                            token_id: *token_id,
+                            text: choice.delta.content,
+                            logprob,
+                        }));
+                        *token_id += 1;
+                    }
+                    if choice.finish_reason.is_some() {
+                        done = true;
+                    }
+                }
+            }
+            Err(e) => tokens.push(Err(LlmError {
+                message: format!("SSE payload parse error: {e}"),
+            })),
         }
     }
+    (tokens, usage, done)
+}
+
+/// Build and fire off the `LlmCalls` row for a completed request. Best-effort: failures are
+/// dropped, mirroring how the rest of the kernel treats metrics emission.
+fn record_llm_call(
+    data_plane: Arc<dyn goni_store::DataPlane>,
+    request_id: Uuid,
+    model: String,
+    usage: Option<Usage>,
+    latency_ms: u32,
+) {
+    tokio::spawn(async move {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("call_id", DataType::Utf8, false),
+            Field::new("request_id", DataType::Utf8, false),
+            Field::new("model_id", DataType::Utf8, false),
+            Field::new("prompt_tokens", DataType::UInt32, false),
+            Field::new("completion_tokens", DataType::UInt32, false),
+            Field::new("total_tokens", DataType::UInt32, false),
+            Field::new("latency_ms", DataType::UInt32, false),
+            Field::new("cache_hit", DataType::Boolean, false),
+        ]));
+        let (prompt_tokens, completion_tokens, total_tokens) = usage
+            .map(|u| (u.prompt_tokens, u.completion_tokens, u.total_tokens))
+            .unwrap_or((0, 0, 0));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec![Uuid::new_v4().to_string()])),
+                Arc::new(StringArray::from(vec![request_id.to_string()])),
+                Arc::new(StringArray::from(vec![model])),
+                Arc::new(UInt32Array::from(vec![prompt_tokens])),
+                Arc::new(UInt32Array::from(vec![completion_tokens])),
+                Arc::new(UInt32Array::from(vec![total_tokens])),
+                Arc::new(UInt32Array::from(vec![latency_ms])),
+                Arc::new(BooleanArray::from(vec![false])),
+            ],
+        );
+        if let Ok(batch) = batch {
+            let _ = data_plane.append_batches("LlmCalls", vec![Arc::new(batch)]).await;
+        }
+    });
 }
 
 #[async_trait]
@@ -81,6 +247,8 @@ impl LlmEngine for HttpVllmEngine {
             max_tokens: Some(req.max_tokens as u32),
             stream: true,
             seed: if self.deterministic { self.seed } else { None },
+            stream_options: StreamOptions { include_usage: true },
+            logprobs: true,
         };
 
         let resp = self
@@ -99,58 +267,178 @@ impl LlmEngine for HttpVllmEngine {
             });
         }
 
-        let stream_body = resp.bytes_stream();
-        let mut token_id: u32 = 0;
-        let s = stream_body.filter_map(move |chunk_res| {
-            let mut token_id_local = token_id;
-            token_id += 1;
+        let request_id = req.request_id;
+        let model = self.model.clone();
+        let data_plane = self.data_plane.clone();
+        let started = Instant::now();
+
+        struct State {
+            body: Pin<Box<dyn futures_core::Stream<Item = reqwest::Result<bytes::Bytes>> + Send>>,
+            buffer: SseBuffer,
+            pending: Vec<Result<LlmToken, LlmError>>,
+            token_id: u32,
+            usage: Option<Usage>,
+            done: bool,
+            reported: bool,
+        }
+
+        let state = State {
+            body: Box::pin(resp.bytes_stream()),
+            buffer: SseBuffer::new(),
+            pending: Vec::new(),
+            token_id: 0,
+            usage: None,
+            done: false,
+            reported: false,
+        };
+
+        let out = stream::unfold(state, move |mut state| {
+            let request_id = request_id;
+            let model = model.clone();
+            let data_plane = data_plane.clone();
             async move {
-                match chunk_res {
-                    Ok(bytes) => {
-                        // vLLM SSE chunks are lines prefixed with "data: "
-                        let text = String::from_utf8_lossy(&bytes);
-                        let mut out_tokens = Vec::new();
-                        for line in text.lines() {
-                            let line = line.trim();
-                            if line.is_empty() || line == "data:" {
-                                continue;
-                            }
-                            let line = line.trim_start_matches("data: ");
-                            if line == "[DONE]" {
-                                continue;
-                            }
-                            if let Ok(chunk) = serde_json::from_str::<ChatCompletionChunk>(line) {
-                                for choice in chunk.choices {
-                                    if !choice.delta.content.is_empty() {
-                                        out_tokens.push(Ok(LlmToken {
-                                            token_id: token_id_local,
-                                            text: choice.delta.content.clone(),
-                                        }));
-                                        token_id_local += 1;
-                                    }
-                                }
+                use futures_util::StreamExt;
+
+                loop {
+                    if !state.pending.is_empty() {
+                        let tok = state.pending.remove(0);
+                        return Some((tok, state));
+                    }
+                    if let Some(frame) = state.buffer.next_frame() {
+                        let (tokens, usage, done) = parse_frame(&frame, &mut state.token_id);
+                        if usage.is_some() {
+                            state.usage = usage;
+                        }
+                        state.done = state.done || done;
+                        state.pending = tokens;
+                        continue;
+                    }
+                    if state.done {
+                        if !state.reported {
+                            state.reported = true;
+                            if let Some(dp) = data_plane {
+                                record_llm_call(
+                                    dp,
+                                    request_id,
+                                    model,
+                                    state.usage,
+                                    started.elapsed().as_millis() as u32,
+                                );
                             }
                         }
-                        if out_tokens.is_empty() {
-                            None
-                        } else {
-                            // emit tokens sequentially
-                            let stream = stream::iter(out_tokens);
-                            Some(stream)
+                        return None;
+                    }
+                    match state.body.next().await {
+                        Some(Ok(bytes)) => state.buffer.push(&bytes),
+                        Some(Err(e)) => {
+                            state.pending.push(Err(LlmError {
+                                message: format!("stream error: {e}"),
+                            }));
+                            state.done = true;
                         }
+                        None => state.done = true,
                     }
-                    Err(e) => Some(stream::iter(vec![Err(LlmError {
-                        message: format!("stream error: {e}"),
-                    })])),
                 }
             }
         });
 
-        // Flatten the stream of streams
-        let flat_stream = s
-            .map(|maybe_stream| maybe_stream.unwrap_or_else(|| stream::empty()))
-            .flatten();
+        Ok(Box::pin(out) as TokenStream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_frame_waits_for_trailing_blank_line() {
+        let mut buf = SseBuffer::new();
+        buf.push(b"data: {\"choices\":[]}");
+        assert!(buf.next_frame().is_none());
+
+        buf.push(b"\n\n");
+        assert_eq!(buf.next_frame().unwrap(), "data: {\"choices\":[]}");
+        assert!(buf.next_frame().is_none());
+    }
+
+    #[test]
+    fn next_frame_splits_a_multi_byte_utf8_codepoint_across_reads() {
+        // "café" — the 'é' is the two-byte UTF-8 sequence 0xC3 0xA9. Split the push right
+        // between those two bytes, as a TCP read boundary could.
+        let line = "data: café\n\n";
+        let bytes = line.as_bytes();
+        let split_in_the_middle_of_e_acute = bytes.iter().position(|&b| b == 0xC3).unwrap() + 1;
+
+        let mut buf = SseBuffer::new();
+        buf.push(&bytes[..split_in_the_middle_of_e_acute]);
+        assert!(buf.next_frame().is_none());
+
+        buf.push(&bytes[split_in_the_middle_of_e_acute..]);
+        assert_eq!(buf.next_frame().unwrap(), "data: café");
+    }
+
+    #[test]
+    fn next_frame_handles_several_frames_split_arbitrarily_across_reads() {
+        let whole = b"data: one\n\ndata: two\n\ndata: three\n\n".to_vec();
+
+        // Push in small, deliberately misaligned chunks rather than per-frame.
+        let mut buf = SseBuffer::new();
+        let mut frames = Vec::new();
+        for chunk in whole.chunks(5) {
+            buf.push(chunk);
+            while let Some(frame) = buf.next_frame() {
+                frames.push(frame);
+            }
+        }
+
+        assert_eq!(frames, vec!["data: one", "data: two", "data: three"]);
+    }
+
+    #[test]
+    fn parse_frame_extracts_token_text_and_advances_token_id() {
+        let frame = r#"data: {"choices":[{"index":0,"delta":{"content":"hi"}}]}"#;
+        let mut token_id = 0;
+        let (tokens, usage, done) = parse_frame(frame, &mut token_id);
+
+        assert_eq!(tokens.len(), 1);
+        let tok = tokens[0].as_ref().unwrap();
+        assert_eq!(tok.text, "hi");
+        assert_eq!(tok.token_id, 0);
+        assert_eq!(token_id, 1);
+        assert!(usage.is_none());
+        assert!(!done);
+    }
+
+    #[test]
+    fn parse_frame_recognizes_done_sentinel_and_finish_reason() {
+        let mut token_id = 0;
+        let (tokens, _usage, done) = parse_frame("data: [DONE]", &mut token_id);
+        assert!(tokens.is_empty());
+        assert!(done);
+
+        let frame = r#"data: {"choices":[{"index":0,"delta":{},"finish_reason":"stop"}]}"#;
+        let (_tokens, _usage, done) = parse_frame(frame, &mut token_id);
+        assert!(done);
+    }
+
+    #[test]
+    fn parse_frame_captures_usage_from_the_terminal_chunk() {
+        let frame = r#"data: {"choices":[],"usage":{"prompt_tokens":3,"completion_tokens":5,"total_tokens":8}}"#;
+        let mut token_id = 0;
+        let (_tokens, usage, _done) = parse_frame(frame, &mut token_id);
+        let usage = usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 3);
+        assert_eq!(usage.completion_tokens, 5);
+        assert_eq!(usage.total_tokens, 8);
+    }
 
-        Ok(Box::pin(flat_stream) as TokenStream)
+    #[test]
+    fn parse_frame_reports_malformed_payload_without_panicking() {
+        let mut token_id = 0;
+        let (tokens, usage, done) = parse_frame("data: not json", &mut token_id);
+        assert_eq!(tokens.len(), 1);
+        assert!(tokens[0].is_err());
+        assert!(usage.is_none());
+        assert!(!done);
     }
 }
@@ -11,6 +11,11 @@ pub use http_vllm::HttpVllmEngine;
 pub struct LlmToken {
     pub token_id: u32,
     pub text: String,
+    /// The engine's log-prob for this token, when it exposes one. This is the per-token quality
+    /// signal `goni_core`'s SPRT escalation policy accumulates into its log-likelihood ratio;
+    /// engines that don't expose logprobs (e.g. [`NullLlmEngine`]) leave escalation without
+    /// evidence to act on.
+    pub logprob: Option<f32>,
 }
 
 pub type TokenStream =
@@ -1,17 +1,78 @@
+use std::collections::HashMap;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::rngs::OsRng;
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Policy {
     pub mode: String,
-    pub allowlist: Vec<String>,
+    pub allowlist: Vec<ScopeGrant>,
+}
+
+/// Access level a [`ScopeGrant`] confers on its target, ordered least to most privileged so
+/// `Privilege`s can be compared with the derived `Ord` (`View < Operate < Manage < Administer`).
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Privilege {
+    View,
+    Operate,
+    Manage,
+    Administer,
 }
 
+/// A single `(target, privilege)` capability grant, modeled on Matter-style ACLs.
+///
+/// `target` is a hierarchical glob: a trailing `/**` segment grants any descendant path (so
+/// `fs.read:~/Documents/**` covers `fs.read:~/Documents/Taxes/2024.pdf`), and a leading `*.`
+/// grants any number of leading labels (so `*.example.com` covers `api.example.com`). A bare `*`
+/// grants everything. Anything else is matched literally.
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct CapabilityToken {
-    pub token_id: Uuid,
-    pub scopes: Vec<String>,
-    pub expires_at: Option<String>,
+pub struct ScopeGrant {
+    pub target: String,
+    pub privilege: Privilege,
+}
+
+impl ScopeGrant {
+    /// Specificity of this grant against `requested`, or `None` if `target` doesn't match.
+    ///
+    /// Specificity is the number of non-wildcard characters in `target`, so a literal match
+    /// always outranks a wildcard one and a longer literal prefix outranks a shorter one; this
+    /// is what lets [`resolve_grant`] pick the most specific of several matching rules.
+    fn match_specificity(&self, requested: &str) -> Option<usize> {
+        scope_target_matches(&self.target, requested).then(|| {
+            if self.target == requested {
+                usize::MAX
+            } else {
+                self.target.chars().filter(|c| *c != '*').count()
+            }
+        })
+    }
+}
+
+fn scope_target_matches(pattern: &str, requested: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+    if let Some(prefix) = pattern.strip_suffix("/**") {
+        return requested == prefix || requested.starts_with(&format!("{prefix}/"));
+    }
+    if let Some(suffix) = pattern.strip_prefix("*.") {
+        return requested == suffix || requested.ends_with(&format!(".{suffix}"));
+    }
+    pattern == requested
+}
+
+/// Resolves the most specific grant in `scopes` that matches `requested`, if any.
+fn resolve_grant<'a>(scopes: &'a [ScopeGrant], requested: &str) -> Option<&'a ScopeGrant> {
+    scopes
+        .iter()
+        .filter_map(|grant| grant.match_specificity(requested).map(|spec| (spec, grant)))
+        .max_by_key(|(spec, _)| *spec)
+        .map(|(_, grant)| grant)
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -21,6 +82,110 @@ pub struct BudgetLedger {
     pub tool_calls_remaining: i64,
 }
 
+/// A signed, bearer capability modeled on the handshake style used by fabaccess-bffh and
+/// aero-sasl: an issuer grants `scopes` plus a starting `budget` and an optional `expires_at`,
+/// then signs the whole thing (Ed25519, same convention as `goni_receipts::ReceiptLog`) so a
+/// holder can present the token to a verifier that never talked to the issuer directly.
+///
+/// `issuer_key_id`/`signature` are empty until [`sign_capability_token`] fills them in; a token
+/// in that state fails [`PolicyEngine::verify_capability`] (`"unsigned_token"`), the same way an
+/// unattributed receipt would fail `verify_log`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CapabilityToken {
+    pub token_id: Uuid,
+    pub scopes: Vec<ScopeGrant>,
+    pub expires_at: Option<String>,
+    pub budget: BudgetLedger,
+    /// Id of the key (see [`PolicyEngine::verify_capability`]'s `issuer_keys` map) whose
+    /// signature covers this token's other fields.
+    pub issuer_key_id: String,
+    /// Base64-encoded detached Ed25519 signature over [`CapabilityToken::signing_payload`].
+    pub signature: String,
+    /// Base64-encoded Ed25519 public key of this token's holder, bound into
+    /// [`CapabilityToken::signing_payload`] so the issuer attests which holder may present it.
+    /// The matching private key is generated and kept by the holder alone — it is never put on
+    /// the wire, only used to sign [`ChallengeNonce`]s (see [`compute_challenge_proof`]) — which
+    /// is what makes the challenge scheme resistant to replay by anyone observing a `/fetch` call.
+    pub holder_key: String,
+}
+
+impl CapabilityToken {
+    /// Canonical hash of every field a verifier must trust (everything but `issuer_key_id`/
+    /// `signature` themselves), mirroring `goni_receipts::hash_receipt`'s chain-hash convention.
+    /// Signing and verification both hash over this so they can never drift apart.
+    fn signing_payload(&self) -> String {
+        let mut h = Sha256::new();
+        h.update(self.token_id.to_string());
+        for grant in &self.scopes {
+            h.update(&grant.target);
+            h.update(format!("{:?}", grant.privilege));
+        }
+        h.update(self.expires_at.as_deref().unwrap_or(""));
+        h.update(self.budget.bytes_remaining.to_string());
+        h.update(self.budget.tokens_remaining.to_string());
+        h.update(self.budget.tool_calls_remaining.to_string());
+        h.update(&self.holder_key);
+        format!("{:x}", h.finalize())
+    }
+}
+
+/// Signs `token` with `signing_key` under `issuer_key_id`, filling in its `signature` field.
+/// Call this once at issuance; a verifier only ever needs the corresponding public key (see
+/// [`PolicyEngine::verify_capability`]).
+pub fn sign_capability_token(mut token: CapabilityToken, signing_key: &SigningKey, issuer_key_id: &str) -> CapabilityToken {
+    let payload = token.signing_payload();
+    let signature: Signature = signing_key.sign(payload.as_bytes());
+    token.signature = BASE64.encode(signature.to_bytes());
+    token.issuer_key_id = issuer_key_id.to_string();
+    token
+}
+
+/// Server-issued nonce for the SASL-style capability challenge (see [`ChallengeProof`]).
+/// Generate a fresh one per handshake and discard it once consumed — reusing a nonce would let a
+/// captured proof be replayed.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChallengeNonce(pub String);
+
+impl ChallengeNonce {
+    /// 32 random bytes, base64-encoded.
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        Self(BASE64.encode(bytes))
+    }
+}
+
+/// A client's response to a [`ChallengeNonce`], proving it holds the holder's private signing key
+/// (the counterpart of `token.holder_key`) — a secret that is never transmitted, unlike `token`
+/// itself (which egress-gate's `/fetch` resends and verifies in full on every call). That's what
+/// makes a captured exchange unreplayable: the nonce is single-use and server-tracked, and seeing
+/// past proofs or the token's own signature never lets an observer compute a new one.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChallengeProof {
+    pub token_id: Uuid,
+    pub nonce: ChallengeNonce,
+    /// Base64 detached Ed25519 signature over `nonce`, verified against `token.holder_key`. Only
+    /// the holder's private key (never transmitted) can produce this, so observing any number of
+    /// past proofs never lets anyone forge one for a new nonce.
+    pub proof: String,
+}
+
+/// Computes the client side of a capability challenge: the proof a holder of `token` sends back
+/// in response to `nonce`, signed with the holder's own private key (the counterpart of
+/// `token.holder_key`). See [`ChallengeProof`] for why this can't be forged by an observer.
+pub fn compute_challenge_proof(
+    token: &CapabilityToken,
+    nonce: &ChallengeNonce,
+    holder_signing_key: &SigningKey,
+) -> ChallengeProof {
+    let signature: Signature = holder_signing_key.sign(nonce.0.as_bytes());
+    ChallengeProof {
+        token_id: token.token_id,
+        nonce: nonce.clone(),
+        proof: BASE64.encode(signature.to_bytes()),
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum PolicyDecision {
     Allow,
@@ -64,7 +229,7 @@ impl PolicyEngine {
         }
     }
 
-    pub fn allowlist(allowlist: Vec<String>) -> Self {
+    pub fn allowlist(allowlist: Vec<ScopeGrant>) -> Self {
         Self {
             policy: Policy {
                 mode: "allowlist".into(),
@@ -73,14 +238,25 @@ impl PolicyEngine {
         }
     }
 
+    /// Resolves the most specific scope granted to `tool_id` and requires it to authorize at
+    /// least `privilege`. The `Deny` reason names the failing rule (or `scope_not_allowed` if no
+    /// rule matched at all) so operators can see exactly why a tool call was blocked.
     pub fn evaluate_tool(
         &self,
         token: &CapabilityToken,
         tool_id: &str,
+        privilege: Privilege,
         ledger: &mut BudgetLedger,
     ) -> PolicyDecision {
-        if !token.scopes.iter().any(|s| s == tool_id || s == "*") {
-            return PolicyDecision::Deny("scope_not_allowed".into());
+        match resolve_grant(&token.scopes, tool_id) {
+            None => return PolicyDecision::Deny("scope_not_allowed".into()),
+            Some(grant) if grant.privilege < privilege => {
+                return PolicyDecision::Deny(format!(
+                    "privilege_exceeds_grant:{}:granted={:?}:requested={:?}",
+                    grant.target, grant.privilege, privilege
+                ));
+            }
+            Some(_) => {}
         }
         if let Err(decision) = ledger.debit_tool_call() {
             return decision;
@@ -88,16 +264,104 @@ impl PolicyEngine {
         PolicyDecision::Allow
     }
 
-    pub fn evaluate_egress(&self, host: &str) -> PolicyDecision {
+    /// Resolves the most specific allowlist rule matching `host` and requires it to authorize at
+    /// least `privilege`, mirroring [`Self::evaluate_tool`].
+    pub fn evaluate_egress(&self, host: &str, privilege: Privilege) -> PolicyDecision {
         if self.policy.mode == "deny" {
             return PolicyDecision::Deny("egress_denied".into());
         }
-        if self.policy.allowlist.iter().any(|h| h == host) {
-            PolicyDecision::Allow
-        } else {
-            PolicyDecision::Deny("host_not_allowed".into())
+        match resolve_grant(&self.policy.allowlist, host) {
+            None => PolicyDecision::Deny("host_not_allowed".into()),
+            Some(grant) if grant.privilege < privilege => PolicyDecision::Deny(format!(
+                "privilege_exceeds_grant:{}:granted={:?}:requested={:?}",
+                grant.target, grant.privilege, privilege
+            )),
+            Some(_) => PolicyDecision::Allow,
         }
     }
+
+    /// Checks `token` itself is trustworthy and in scope for `bound_action` — signature,
+    /// expiry, and scope presence — *before* `evaluate_tool`/`evaluate_egress` run their
+    /// privilege/budget checks. `issuer_keys` maps `issuer_key_id` -> public key, the same
+    /// rotation-friendly shape `goni_receipts::verify_log` takes. `now` is an RFC3339 timestamp
+    /// (e.g. `chrono::Utc::now().to_rfc3339()`); comparing it lexicographically against
+    /// `expires_at` avoids pulling a datetime crate into this one.
+    pub fn verify_capability(
+        &self,
+        token: &CapabilityToken,
+        bound_action: &str,
+        issuer_keys: &HashMap<String, VerifyingKey>,
+        now: &str,
+    ) -> PolicyDecision {
+        if token.signature.is_empty() {
+            return PolicyDecision::Deny("unsigned_token".into());
+        }
+        let Some(key) = issuer_keys.get(&token.issuer_key_id) else {
+            return PolicyDecision::Deny(format!("unknown_issuer_key:{}", token.issuer_key_id));
+        };
+        let Ok(sig_bytes) = BASE64.decode(&token.signature) else {
+            return PolicyDecision::Deny("invalid_signature_encoding".into());
+        };
+        let Ok(sig_bytes): Result<[u8; 64], _> = sig_bytes.try_into() else {
+            return PolicyDecision::Deny("invalid_signature_encoding".into());
+        };
+        let signature = Signature::from_bytes(&sig_bytes);
+        if key.verify(token.signing_payload().as_bytes(), &signature).is_err() {
+            return PolicyDecision::Deny("invalid_signature".into());
+        }
+
+        if let Some(expires_at) = &token.expires_at {
+            if now >= expires_at.as_str() {
+                return PolicyDecision::Deny("token_expired".into());
+            }
+        }
+
+        if resolve_grant(&token.scopes, bound_action).is_none() {
+            return PolicyDecision::Deny("scope_not_allowed".into());
+        }
+
+        PolicyDecision::Allow
+    }
+
+    /// Verifies a SASL-style challenge response: `proof` must name the `expected_nonce` this
+    /// engine handed out and must be a valid Ed25519 signature over it under `token.holder_key`
+    /// (see [`compute_challenge_proof`]) — a key never sent alongside `token` itself, so capturing
+    /// a `/fetch` call (which does resend the full token) never lets an observer forge a new
+    /// proof. Callers should still run [`Self::verify_capability`] afterwards — this only proves
+    /// possession, not that the token is unexpired or in scope.
+    pub fn verify_challenge_proof(
+        &self,
+        token: &CapabilityToken,
+        expected_nonce: &ChallengeNonce,
+        proof: &ChallengeProof,
+    ) -> PolicyDecision {
+        if proof.token_id != token.token_id {
+            return PolicyDecision::Deny("token_id_mismatch".into());
+        }
+        if proof.nonce != *expected_nonce {
+            return PolicyDecision::Deny("stale_or_unknown_nonce".into());
+        }
+        let Ok(holder_key_bytes) = BASE64.decode(&token.holder_key) else {
+            return PolicyDecision::Deny("invalid_holder_key_encoding".into());
+        };
+        let Ok(holder_key_bytes): Result<[u8; 32], _> = holder_key_bytes.try_into() else {
+            return PolicyDecision::Deny("invalid_holder_key_encoding".into());
+        };
+        let Ok(holder_key) = VerifyingKey::from_bytes(&holder_key_bytes) else {
+            return PolicyDecision::Deny("invalid_holder_key".into());
+        };
+        let Ok(sig_bytes) = BASE64.decode(&proof.proof) else {
+            return PolicyDecision::Deny("invalid_proof_encoding".into());
+        };
+        let Ok(sig_bytes): Result<[u8; 64], _> = sig_bytes.try_into() else {
+            return PolicyDecision::Deny("invalid_proof_encoding".into());
+        };
+        let signature = Signature::from_bytes(&sig_bytes);
+        if holder_key.verify(expected_nonce.0.as_bytes(), &signature).is_err() {
+            return PolicyDecision::Deny("challenge_proof_invalid".into());
+        }
+        PolicyDecision::Allow
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -183,14 +447,212 @@ mod tests {
         let engine = PolicyEngine::default_deny();
         let token = CapabilityToken {
             token_id: Uuid::new_v4(),
-            scopes: vec!["demo.echo".into()],
+            scopes: vec![ScopeGrant {
+                target: "demo.echo".into(),
+                privilege: Privilege::Operate,
+            }],
             expires_at: None,
+            budget: BudgetLedger::new(1, 1, 1),
+            issuer_key_id: String::new(),
+            signature: String::new(),
+            holder_key: String::new(),
         };
         let mut ledger = BudgetLedger::new(1, 1, 1);
-        let decision = engine.evaluate_tool(&token, "other.tool", &mut ledger);
+        let decision = engine.evaluate_tool(&token, "other.tool", Privilege::Operate, &mut ledger);
         assert!(matches!(decision, PolicyDecision::Deny(_)));
     }
 
+    #[test]
+    fn scope_prefix_grants_descendant_path() {
+        let token = CapabilityToken {
+            token_id: Uuid::new_v4(),
+            scopes: vec![ScopeGrant {
+                target: "fs.read:~/Documents/**".into(),
+                privilege: Privilege::View,
+            }],
+            expires_at: None,
+            budget: BudgetLedger::new(1, 1, 1),
+            issuer_key_id: String::new(),
+            signature: String::new(),
+            holder_key: String::new(),
+        };
+        let mut ledger = BudgetLedger::new(1, 1, 1);
+        let decision = PolicyEngine::allowlist(Vec::new()).evaluate_tool(
+            &token,
+            "fs.read:~/Documents/Taxes/2024.pdf",
+            Privilege::View,
+            &mut ledger,
+        );
+        assert!(matches!(decision, PolicyDecision::Allow));
+    }
+
+    #[test]
+    fn egress_wildcard_matches_subdomain() {
+        let engine = PolicyEngine::allowlist(vec![ScopeGrant {
+            target: "*.example.com".into(),
+            privilege: Privilege::Operate,
+        }]);
+        let decision = engine.evaluate_egress("api.example.com", Privilege::Operate);
+        assert!(matches!(decision, PolicyDecision::Allow));
+        let decision = engine.evaluate_egress("example.org", Privilege::Operate);
+        assert!(matches!(decision, PolicyDecision::Deny(_)));
+    }
+
+    #[test]
+    fn privilege_downgrade_is_denied() {
+        let token = CapabilityToken {
+            token_id: Uuid::new_v4(),
+            scopes: vec![ScopeGrant {
+                target: "fs.read:~/Documents/**".into(),
+                privilege: Privilege::View,
+            }],
+            expires_at: None,
+            budget: BudgetLedger::new(1, 1, 1),
+            issuer_key_id: String::new(),
+            signature: String::new(),
+            holder_key: String::new(),
+        };
+        let mut ledger = BudgetLedger::new(1, 1, 1);
+        let decision = PolicyEngine::allowlist(Vec::new()).evaluate_tool(
+            &token,
+            "fs.read:~/Documents/Taxes/2024.pdf",
+            Privilege::Manage,
+            &mut ledger,
+        );
+        assert!(matches!(decision, PolicyDecision::Deny(_)));
+    }
+
+    #[test]
+    fn most_specific_rule_wins() {
+        let token = CapabilityToken {
+            token_id: Uuid::new_v4(),
+            scopes: vec![
+                ScopeGrant {
+                    target: "*".into(),
+                    privilege: Privilege::View,
+                },
+                ScopeGrant {
+                    target: "fs.read:~/Documents/**".into(),
+                    privilege: Privilege::Manage,
+                },
+            ],
+            expires_at: None,
+            budget: BudgetLedger::new(1, 1, 1),
+            issuer_key_id: String::new(),
+            signature: String::new(),
+            holder_key: String::new(),
+        };
+        let mut ledger = BudgetLedger::new(1, 1, 1);
+        let decision = PolicyEngine::allowlist(Vec::new()).evaluate_tool(
+            &token,
+            "fs.read:~/Documents/Taxes/2024.pdf",
+            Privilege::Manage,
+            &mut ledger,
+        );
+        assert!(matches!(decision, PolicyDecision::Allow));
+    }
+
+    fn signed_token(
+        scopes: Vec<ScopeGrant>,
+        expires_at: Option<String>,
+    ) -> (CapabilityToken, HashMap<String, VerifyingKey>, SigningKey) {
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let holder_signing_key = SigningKey::generate(&mut OsRng);
+        let unsigned = CapabilityToken {
+            token_id: Uuid::new_v4(),
+            scopes,
+            expires_at,
+            budget: BudgetLedger::new(1, 1, 1),
+            issuer_key_id: String::new(),
+            signature: String::new(),
+            holder_key: BASE64.encode(holder_signing_key.verifying_key().to_bytes()),
+        };
+        let token = sign_capability_token(unsigned, &signing_key, "k1");
+        let mut keys = HashMap::new();
+        keys.insert("k1".to_string(), signing_key.verifying_key());
+        (token, keys, holder_signing_key)
+    }
+
+    #[test]
+    fn verify_capability_accepts_correctly_signed_token() {
+        let (token, keys, _holder_key) = signed_token(
+            vec![ScopeGrant { target: "demo.echo".into(), privilege: Privilege::Operate }],
+            None,
+        );
+        let decision = PolicyEngine::default_deny().verify_capability(&token, "demo.echo", &keys, "2026-01-01T00:00:00Z");
+        assert!(matches!(decision, PolicyDecision::Allow));
+    }
+
+    #[test]
+    fn verify_capability_rejects_tampered_scope() {
+        let (mut token, keys, _holder_key) = signed_token(
+            vec![ScopeGrant { target: "demo.echo".into(), privilege: Privilege::Operate }],
+            None,
+        );
+        token.scopes[0].privilege = Privilege::Administer;
+        let decision = PolicyEngine::default_deny().verify_capability(&token, "demo.echo", &keys, "2026-01-01T00:00:00Z");
+        assert!(matches!(decision, PolicyDecision::Deny(_)));
+    }
+
+    #[test]
+    fn verify_capability_rejects_expired_token() {
+        let (token, keys, _holder_key) = signed_token(
+            vec![ScopeGrant { target: "demo.echo".into(), privilege: Privilege::Operate }],
+            Some("2020-01-01T00:00:00Z".into()),
+        );
+        let decision = PolicyEngine::default_deny().verify_capability(&token, "demo.echo", &keys, "2026-01-01T00:00:00Z");
+        assert!(matches!(decision, PolicyDecision::Deny(_)));
+    }
+
+    #[test]
+    fn verify_capability_rejects_out_of_scope_action() {
+        let (token, keys, _holder_key) = signed_token(
+            vec![ScopeGrant { target: "demo.echo".into(), privilege: Privilege::Operate }],
+            None,
+        );
+        let decision = PolicyEngine::default_deny().verify_capability(&token, "other.tool", &keys, "2026-01-01T00:00:00Z");
+        assert!(matches!(decision, PolicyDecision::Deny(_)));
+    }
+
+    #[test]
+    fn challenge_proof_round_trips_and_rejects_wrong_nonce() {
+        let (token, _keys, holder_signing_key) = signed_token(
+            vec![ScopeGrant { target: "demo.echo".into(), privilege: Privilege::Operate }],
+            None,
+        );
+        let engine = PolicyEngine::default_deny();
+        let nonce = ChallengeNonce::generate();
+        let proof = compute_challenge_proof(&token, &nonce, &holder_signing_key);
+        assert!(matches!(engine.verify_challenge_proof(&token, &nonce, &proof), PolicyDecision::Allow));
+
+        let other_nonce = ChallengeNonce::generate();
+        assert!(matches!(
+            engine.verify_challenge_proof(&token, &other_nonce, &proof),
+            PolicyDecision::Deny(_)
+        ));
+    }
+
+    /// The whole point of the challenge scheme: `token` (including `.signature`) is resent in
+    /// full on every protected call, so an observer who only has that — not the holder's private
+    /// key — must not be able to forge a proof for a fresh nonce.
+    #[test]
+    fn challenge_proof_cannot_be_forged_from_observed_token_alone() {
+        let (token, _keys, _holder_signing_key) = signed_token(
+            vec![ScopeGrant { target: "demo.echo".into(), privilege: Privilege::Operate }],
+            None,
+        );
+        let engine = PolicyEngine::default_deny();
+        let nonce = ChallengeNonce::generate();
+
+        let attacker_signing_key = SigningKey::generate(&mut OsRng);
+        let forged = compute_challenge_proof(&token, &nonce, &attacker_signing_key);
+
+        assert!(matches!(
+            engine.verify_challenge_proof(&token, &nonce, &forged),
+            PolicyDecision::Deny(_)
+        ));
+    }
+
     #[test]
     fn memory_write_requires_evidence() {
         let req = MemoryWriteRequest {
@@ -0,0 +1,96 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+use crate::ReceiptError;
+
+/// Storage for a receipt log's raw (possibly encrypted) records, decoupling [`crate::ReceiptLog`]
+/// from where the bytes actually live. `position` is the record's 0-based index in the chain;
+/// implementations that address records individually (e.g. one object per record) need it to
+/// name the record, backends that are naturally ordered (e.g. an append-only file) can ignore it.
+#[async_trait]
+pub trait ReceiptBackend: Send + Sync {
+    /// Durably append one record. Must not reorder or drop records already appended.
+    async fn append(&self, position: u64, record: &[u8]) -> Result<(), ReceiptError>;
+
+    /// Read every record, in position order.
+    async fn read_all(&self) -> Result<Vec<Vec<u8>>, ReceiptError>;
+}
+
+/// Local append-only file backend — the original (and still default) storage for a receipt log.
+pub struct LocalFileBackend {
+    path: PathBuf,
+}
+
+impl LocalFileBackend {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl ReceiptBackend for LocalFileBackend {
+    async fn append(&self, _position: u64, record: &[u8]) -> Result<(), ReceiptError> {
+        // `record` may be arbitrary (possibly encrypted) binary and can legitimately contain a
+        // raw `0x0A` byte, so each line is base64-encoded before the newline delimiter is added —
+        // framing on raw bytes plus `\n` would silently split or corrupt such a record.
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| ReceiptError::Io(e.to_string()))?;
+        let line = BASE64.encode(record);
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.write_all(b"\n"))
+            .map_err(|e| ReceiptError::Io(e.to_string()))
+    }
+
+    async fn read_all(&self) -> Result<Vec<Vec<u8>>, ReceiptError> {
+        read_lines(&self.path)
+    }
+}
+
+fn read_lines(path: &Path) -> Result<Vec<Vec<u8>>, ReceiptError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path).map_err(|e| ReceiptError::Io(e.to_string()))?;
+    content
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            BASE64
+                .decode(line)
+                .map_err(|e| ReceiptError::Io(format!("corrupt receipt log line: {e}")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn round_trips_records_containing_raw_newline_bytes() {
+        let path = "target/test_backend_embedded_newline.jsonl";
+        let _ = std::fs::remove_file(path);
+        let backend = LocalFileBackend::new(path);
+
+        // Stand-ins for ciphertext that happens to contain a literal 0x0A byte, which the old
+        // raw-bytes-plus-\n framing would have split into two records.
+        let records: Vec<Vec<u8>> = vec![
+            vec![1, 2, b'\n', 3, 4],
+            vec![b'\n', b'\n', 5],
+            vec![6, 7, 8],
+        ];
+        for (i, record) in records.iter().enumerate() {
+            backend.append(i as u64, record).await.unwrap();
+        }
+
+        let read_back = backend.read_all().await.unwrap();
+        assert_eq!(read_back, records);
+    }
+}
@@ -0,0 +1,122 @@
+//! Canonical binary encoding for `serde_json::Value`, following the canonical-ordering
+//! discipline of the Preserves format used in syndicate-rs: every value is type-tagged and
+//! length-prefixed, and object keys are sorted by their *encoded* byte order rather than by
+//! source-map insertion order. Two semantically identical values — regardless of which
+//! serializer produced them, what order their object keys were written in, or how a float was
+//! printed — always encode to the same bytes, so [`canonical_hash`] is stable across languages.
+
+use serde_json::{Number, Value};
+use sha2::{Digest, Sha256};
+
+const TAG_NULL: u8 = 0x00;
+const TAG_FALSE: u8 = 0x01;
+const TAG_TRUE: u8 = 0x02;
+const TAG_NUMBER: u8 = 0x03;
+const TAG_STRING: u8 = 0x04;
+const TAG_ARRAY: u8 = 0x05;
+const TAG_OBJECT: u8 = 0x06;
+
+/// SHA-256 over [`canonical_encode`]'s output.
+pub fn canonical_hash(value: &Value) -> [u8; 32] {
+    Sha256::digest(canonical_encode(value)).into()
+}
+
+/// Encodes `value` into the canonical byte form described at module level.
+pub fn canonical_encode(value: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_into(value, &mut out);
+    out
+}
+
+fn encode_into(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(TAG_NULL),
+        Value::Bool(false) => out.push(TAG_FALSE),
+        Value::Bool(true) => out.push(TAG_TRUE),
+        Value::Number(n) => {
+            out.push(TAG_NUMBER);
+            encode_len_prefixed(canonical_number_text(n).as_bytes(), out);
+        }
+        Value::String(s) => {
+            out.push(TAG_STRING);
+            encode_len_prefixed(s.as_bytes(), out);
+        }
+        Value::Array(items) => {
+            out.push(TAG_ARRAY);
+            out.extend_from_slice(&(items.len() as u64).to_be_bytes());
+            for item in items {
+                encode_into(item, out);
+            }
+        }
+        Value::Object(map) => {
+            out.push(TAG_OBJECT);
+            let mut entries: Vec<(Vec<u8>, &Value)> = map
+                .iter()
+                .map(|(k, v)| {
+                    let mut key_buf = Vec::new();
+                    encode_len_prefixed(k.as_bytes(), &mut key_buf);
+                    (key_buf, v)
+                })
+                .collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            out.extend_from_slice(&(entries.len() as u64).to_be_bytes());
+            for (key_buf, v) in entries {
+                out.extend_from_slice(&key_buf);
+                encode_into(v, out);
+            }
+        }
+    }
+}
+
+fn encode_len_prefixed(bytes: &[u8], out: &mut Vec<u8>) {
+    out.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+/// One canonical text form per numeric kind (unsigned/signed/float), so `5` and `5.0` — which
+/// `serde_json` represents differently — never collide, and a float always encodes via its
+/// shortest round-tripping `Debug` form regardless of how its source text was written.
+fn canonical_number_text(n: &Number) -> String {
+    if let Some(u) = n.as_u64() {
+        format!("u{u}")
+    } else if let Some(i) = n.as_i64() {
+        format!("i{i}")
+    } else if let Some(f) = n.as_f64() {
+        format!("f{f:?}")
+    } else {
+        format!("?{n}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn key_order_does_not_affect_hash() {
+        let a = json!({"a": 1, "b": 2});
+        let b = json!({"b": 2, "a": 1});
+        assert_eq!(canonical_hash(&a), canonical_hash(&b));
+    }
+
+    #[test]
+    fn different_values_hash_differently() {
+        let a = json!({"tool": "fetch", "args": [1, 2]});
+        let b = json!({"tool": "fetch", "args": [1, 3]});
+        assert_ne!(canonical_hash(&a), canonical_hash(&b));
+    }
+
+    #[test]
+    fn integer_and_float_do_not_collide() {
+        let a = json!({"n": 5});
+        let b = json!({"n": 5.0});
+        assert_ne!(canonical_hash(&a), canonical_hash(&b));
+    }
+
+    #[test]
+    fn nested_structures_are_deterministic() {
+        let value = json!({"z": [1, {"y": true, "x": null}], "a": "hello"});
+        assert_eq!(canonical_hash(&value), canonical_hash(&value.clone()));
+    }
+}
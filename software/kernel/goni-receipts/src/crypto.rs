@@ -0,0 +1,91 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chacha20poly1305::{aead::Aead, ChaCha20Poly1305, Key, KeyInit, Nonce};
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use crate::ReceiptError;
+
+/// Per-log symmetric AEAD key for envelope encryption of receipts at rest, so a log stored on a
+/// shared or remote [`crate::ReceiptBackend`] stays confidential to holders of the key. Each
+/// record is sealed independently under a nonce derived from its position in the chain —
+/// positions are unique and strictly increasing per log, so this never reuses a nonce without
+/// needing to persist one alongside the ciphertext.
+pub struct ReceiptCipher {
+    cipher: ChaCha20Poly1305,
+}
+
+impl ReceiptCipher {
+    pub fn new(key: [u8; 32]) -> Self {
+        Self {
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&key)),
+        }
+    }
+
+    /// Generate a fresh random data key, returned alongside the cipher so the caller can persist
+    /// it (e.g. under `GONI_RECEIPT_DATA_KEY`, or wrapped by a separate master key).
+    pub fn generate() -> (Self, [u8; 32]) {
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        (Self::new(key), key)
+    }
+
+    fn nonce_for_position(position: u64) -> Nonce {
+        let mut bytes = [0u8; 12];
+        bytes[..8].copy_from_slice(&position.to_be_bytes());
+        *Nonce::from_slice(&bytes)
+    }
+
+    pub fn seal(&self, position: u64, plaintext: &[u8]) -> Result<Vec<u8>, ReceiptError> {
+        self.cipher
+            .encrypt(&Self::nonce_for_position(position), plaintext)
+            .map_err(|e| ReceiptError::Crypto(e.to_string()))
+    }
+
+    pub fn open(&self, position: u64, ciphertext: &[u8]) -> Result<Vec<u8>, ReceiptError> {
+        self.cipher
+            .decrypt(&Self::nonce_for_position(position), ciphertext)
+            .map_err(|e| ReceiptError::Crypto(e.to_string()))
+    }
+}
+
+/// Load an optional [`ReceiptCipher`] from `key_var` (a base64-encoded 32-byte key). Returns
+/// `None` (receipts stored in plaintext) when the var is unset; also `None`, with a warning,
+/// when it's set but unparsable — encryption is opt-in, so a bad key disables it rather than
+/// failing startup the way a bad signing key does in `signing_key_from_env`.
+pub fn receipt_cipher_from_env(key_var: &str) -> Option<ReceiptCipher> {
+    let b64 = std::env::var(key_var).ok()?;
+    let bytes = match BASE64.decode(&b64) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("{key_var} is set but invalid ({e}); receipts will not be encrypted at rest");
+            return None;
+        }
+    };
+    match <[u8; 32]>::try_from(bytes.as_slice()) {
+        Ok(key) => Some(ReceiptCipher::new(key)),
+        Err(_) => {
+            eprintln!("{key_var} must decode to a 32-byte key; receipts will not be encrypted at rest");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_then_open_round_trips() {
+        let (cipher, _key) = ReceiptCipher::generate();
+        let ciphertext = cipher.seal(3, b"hello receipt").unwrap();
+        let plaintext = cipher.open(3, &ciphertext).unwrap();
+        assert_eq!(plaintext, b"hello receipt");
+    }
+
+    #[test]
+    fn opening_at_the_wrong_position_fails() {
+        let (cipher, _key) = ReceiptCipher::generate();
+        let ciphertext = cipher.seal(3, b"hello receipt").unwrap();
+        assert!(cipher.open(4, &ciphertext).is_err());
+    }
+}
@@ -1,18 +1,40 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
-use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Arc;
 use thiserror::Error;
+use tokio::sync::Mutex;
 use uuid::Uuid;
 
+pub mod backend;
+pub use backend::{LocalFileBackend, ReceiptBackend};
+
+pub mod object_store;
+pub use object_store::ObjectStoreBackend;
+
+pub mod crypto;
+pub use crypto::{receipt_cipher_from_env, ReceiptCipher};
+
+pub mod bayou;
+pub use bayou::{default_dep_check, default_merge, reconcile_tentative, AcceptStamp, Csn, TentativeOp};
+
+pub mod canonical;
+pub use canonical::{canonical_encode, canonical_hash};
+
 #[derive(Debug, Error)]
 pub enum ReceiptError {
     #[error("io error: {0}")]
     Io(String),
     #[error("parse error: {0}")]
     Parse(String),
+    #[error("crypto error: {0}")]
+    Crypto(String),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -22,63 +44,461 @@ pub struct Receipt {
     pub action_type: String,
     pub policy_decision: String,
     pub capability_id: Option<Uuid>,
+    /// Outcome of `goni_policy::PolicyEngine::verify_capability` (e.g. `"allow"`,
+    /// `"deny:token_expired"`), recorded alongside `policy_decision` so a reader can tell
+    /// whether a denial came from the capability layer (bad signature, expired, out of scope)
+    /// or from the privilege/budget check that ran after it.
+    pub capability_check: Option<String>,
     pub input_hash: String,
     pub output_hash: String,
     pub prev_hash: Option<String>,
     pub chain_hash: String,
+    /// Id of the key (see [`ReceiptLog::open`]) whose signature covers `chain_hash`; lets
+    /// `verify_log` support key rotation instead of pinning one public key for the whole log.
+    pub signer_key_id: String,
+    /// Base64-encoded detached Ed25519 signature over `chain_hash`'s bytes.
+    pub signature: String,
+}
+
+/// Opaque cursor into the receipt chain for incremental sync (see [`ReceiptLog::sync_page`]).
+///
+/// A token is `position` (how many receipts the client has already seen) plus the `chain_hash`
+/// of the receipt at `position - 1`, so replaying it against a log that has since been
+/// truncated, rotated, or forked is detectable: the hash at that position won't match and the
+/// caller gets [`SyncResult::ResyncRequired`] instead of a silently wrong diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncToken {
+    pub position: u64,
+    pub chain_hash: String,
+}
+
+impl SyncToken {
+    /// `position.chain_hash`; this (rather than e.g. JSON) round-trips through a URL query
+    /// parameter without needing percent-encoding.
+    pub fn encode(&self) -> String {
+        format!("{}.{}", self.position, self.chain_hash)
+    }
+
+    pub fn decode(token: &str) -> Result<Self, ReceiptError> {
+        let (pos, hash) = token
+            .split_once('.')
+            .ok_or_else(|| ReceiptError::Parse(format!("malformed sync token: '{token}'")))?;
+        let position = pos
+            .parse::<u64>()
+            .map_err(|_| ReceiptError::Parse(format!("malformed sync token: '{token}'")))?;
+        Ok(Self {
+            position,
+            chain_hash: hash.to_string(),
+        })
+    }
+}
+
+/// Result of [`ReceiptLog::sync_page`].
+#[derive(Debug, Clone)]
+pub enum SyncResult {
+    /// `receipts` appended since the requested token, plus the token to resume from next time.
+    Ok {
+        receipts: Vec<Receipt>,
+        next_token: SyncToken,
+    },
+    /// The requested token no longer matches the chain (stale, forked, or from a rotated log);
+    /// the caller must discard its cursor and re-sync from the start.
+    ResyncRequired,
+}
+
+/// Where the chain currently stands: guarded by one lock so two concurrent `append` calls can't
+/// each read the same tail and fork the chain.
+struct ChainState {
+    last_hash: Option<String>,
+    next_position: u64,
+}
+
+/// A log's role in anti-entropy reconciliation (see [`ReceiptLog::sync`]). Exactly one
+/// replica in a deployment should be [`ReplicaRole::Primary`] — it's the only one that assigns
+/// CSNs (a committed receipt's backend position *is* its CSN). Everyone else is
+/// [`ReplicaRole::Secondary`] and stages writes as [`TentativeOp`]s until they've reconciled.
+#[derive(Clone, Copy, Debug)]
+pub enum ReplicaRole {
+    Primary,
+    Secondary { replica_id: u32 },
 }
 
+/// Durable, append-only, tamper-evident receipt log.
+///
+/// The hash chain alone (`prev_hash`/`chain_hash`) only catches truncation: anyone who can write
+/// to `backend` can recompute every `chain_hash`, since nothing in it is secret. Each appended
+/// receipt is additionally signed with `signing_key`, so forging a line also requires the
+/// private key, not just write access to the backend. Storage is pluggable via
+/// [`ReceiptBackend`] ([`LocalFileBackend`] for the original single-file layout,
+/// [`ObjectStoreBackend`] for S3/Garage-style remote storage), and records can optionally be
+/// sealed with a [`ReceiptCipher`] so a shared or remote backend never sees plaintext.
+///
+/// A log can also run weakly connected (see [`ReplicaRole`]): a [`ReplicaRole::Secondary`]
+/// accepts writes locally via [`ReceiptLog::accept`] even while disconnected from the primary,
+/// and [`ReceiptLog::sync`] later brings it back in sync, Bayou-style — the hash chain
+/// doubles as the checkpoint integrity mechanism anti-entropy reconciles against.
 pub struct ReceiptLog {
-    path: PathBuf,
-    last_hash: Mutex<Option<String>>,
+    backend: Arc<dyn ReceiptBackend>,
+    cipher: Option<ReceiptCipher>,
+    state: Mutex<ChainState>,
+    signing_key: SigningKey,
+    key_id: String,
+    role: ReplicaRole,
+    /// Writes accepted locally but not yet assigned a CSN; empty for a log that has never run as
+    /// (or reconciled with) a secondary. Ordered by `AcceptStamp`.
+    tentative: Mutex<Vec<TentativeOp>>,
 }
 
 impl ReceiptLog {
-    pub fn open(path: impl AsRef<Path>) -> Result<Self, ReceiptError> {
-        let path = path.as_ref().to_path_buf();
-        let last_hash = read_last_hash(&path)?;
+    /// Open (or create) a log backed by a single local file — the original layout.
+    /// `signing_key`/`key_id` sign every appended receipt; `key_id` is stored on the receipt so
+    /// `verify_log` can look up the matching public key even after the signing key rotates.
+    pub async fn open(
+        path: impl AsRef<Path>,
+        signing_key: SigningKey,
+        key_id: impl Into<String>,
+    ) -> Result<Self, ReceiptError> {
+        Self::with_backend(
+            Arc::new(LocalFileBackend::new(path.as_ref())),
+            signing_key,
+            key_id,
+            None,
+        )
+        .await
+    }
+
+    /// Open (or create) a log against any [`ReceiptBackend`], optionally encrypting every record
+    /// at rest under `cipher`. Replays the backend once, up front, to recover the chain's current
+    /// length and tail hash. Runs as [`ReplicaRole::Primary`]; use [`ReceiptLog::with_role`] to
+    /// open a secondary replica instead.
+    pub async fn with_backend(
+        backend: Arc<dyn ReceiptBackend>,
+        signing_key: SigningKey,
+        key_id: impl Into<String>,
+        cipher: Option<ReceiptCipher>,
+    ) -> Result<Self, ReceiptError> {
+        Self::with_role(backend, signing_key, key_id, cipher, ReplicaRole::Primary).await
+    }
+
+    /// Like [`ReceiptLog::with_backend`], but lets the caller pick this replica's role.
+    pub async fn with_role(
+        backend: Arc<dyn ReceiptBackend>,
+        signing_key: SigningKey,
+        key_id: impl Into<String>,
+        cipher: Option<ReceiptCipher>,
+        role: ReplicaRole,
+    ) -> Result<Self, ReceiptError> {
+        let all = decode_records(backend.as_ref(), cipher.as_ref()).await?;
+        let state = ChainState {
+            last_hash: all.last().map(|r| r.chain_hash.clone()),
+            next_position: all.len() as u64,
+        };
         Ok(Self {
-            path,
-            last_hash: Mutex::new(last_hash),
+            backend,
+            cipher,
+            state: Mutex::new(state),
+            signing_key,
+            key_id: key_id.into(),
+            role,
+            tentative: Mutex::new(Vec::new()),
         })
     }
 
-    pub fn append(&self, mut receipt: Receipt) -> Result<(), ReceiptError> {
-        let mut last = self.last_hash.lock().map_err(|_| ReceiptError::Io("lock".into()))?;
-        receipt.prev_hash = last.clone();
+    /// Accept `receipt` into the log. On the primary this commits immediately — the backend
+    /// position *is* the CSN, identical to the pre-replication `append` behavior. On a secondary
+    /// it's staged as a [`TentativeOp`], ordered by `(accept_timestamp, replica_id)`. A sync
+    /// does not commit it: it leaves the tentative suffix once the primary has committed it
+    /// (see [`ReceiptLog::commit_oldest_tentative`]) and a later [`ReceiptLog::sync`] pulls
+    /// that commit in.
+    pub async fn accept(&self, receipt: Receipt) -> Result<(), ReceiptError> {
+        match self.role {
+            ReplicaRole::Primary => self.append(receipt).await,
+            ReplicaRole::Secondary { replica_id } => {
+                let stamp = AcceptStamp {
+                    accept_timestamp: now_millis(),
+                    replica_id,
+                };
+                let mut tentative = self.tentative.lock().await;
+                tentative.push(TentativeOp {
+                    stamp,
+                    receipt,
+                    dep_check: default_dep_check(),
+                    merge: default_merge(),
+                });
+                tentative.sort_by_key(|op| op.stamp);
+                Ok(())
+            }
+        }
+    }
+
+    /// Primary-only: promote the oldest outstanding tentative op (if any) into the committed
+    /// chain, assigning it the next CSN. Secondaries never assign CSNs themselves — they pick up
+    /// commits from the primary via [`ReceiptLog::sync`] — so this is a no-op there.
+    pub async fn commit_oldest_tentative(&self) -> Result<bool, ReceiptError> {
+        if !matches!(self.role, ReplicaRole::Primary) {
+            return Ok(false);
+        }
+        let next = {
+            let mut tentative = self.tentative.lock().await;
+            if tentative.is_empty() {
+                None
+            } else {
+                Some(tentative.remove(0))
+            }
+        };
+        let Some(op) = next else { return Ok(false) };
+        self.append(op.receipt).await?;
+        Ok(true)
+    }
+
+    /// Anti-entropy reconciliation with `peer`: pulls any receipts `peer` has committed beyond
+    /// this node's own committed length directly into this node's backend (a committed prefix is
+    /// just a linear, already-totally-ordered log, so catching up means replaying `peer`'s tail),
+    /// then rebuilds both nodes' tentative suffix as the canonical merge of the two via
+    /// [`reconcile_tentative`]. Returns how many committed receipts were pulled. Two replicas
+    /// that have reconciled against the same primary end up with an identical committed prefix
+    /// and tentative suffix — the convergence invariant this module exists for.
+    pub async fn sync(&self, peer: &ReceiptLog) -> Result<usize, ReceiptError> {
+        let peer_committed = peer.read_all().await?;
+
+        let pulled = {
+            let mut state = self.state.lock().await;
+            let start = state.next_position as usize;
+            let mut pulled = 0;
+            for receipt in peer_committed.iter().skip(start) {
+                // Re-serialize under our own cipher/position rather than copying peer's raw
+                // bytes: positions (and therefore nonces, see `ReceiptCipher`) are per-backend.
+                let plaintext =
+                    serde_json::to_vec(receipt).map_err(|e| ReceiptError::Parse(e.to_string()))?;
+                let record = match &self.cipher {
+                    Some(cipher) => cipher.seal(state.next_position, &plaintext)?,
+                    None => plaintext,
+                };
+                self.backend.append(state.next_position, &record).await?;
+                state.last_hash = Some(receipt.chain_hash.clone());
+                state.next_position += 1;
+                pulled += 1;
+            }
+            pulled
+        };
+
+        let mut committed_ids: std::collections::HashSet<Uuid> =
+            peer_committed.iter().map(|r| r.receipt_id).collect();
+        committed_ids.extend(self.read_all().await?.iter().map(|r| r.receipt_id));
+
+        let local_tentative = std::mem::take(&mut *self.tentative.lock().await);
+        let peer_tentative = std::mem::take(&mut *peer.tentative.lock().await);
+        let merged = reconcile_tentative(local_tentative, peer_tentative, &committed_ids);
+
+        *self.tentative.lock().await = merged.clone();
+        *peer.tentative.lock().await = merged;
+
+        Ok(pulled)
+    }
+
+    pub async fn append(&self, mut receipt: Receipt) -> Result<(), ReceiptError> {
+        let mut state = self.state.lock().await;
+        receipt.prev_hash = state.last_hash.clone();
         receipt.chain_hash = hash_receipt(&receipt);
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.path)
-            .map_err(|e| ReceiptError::Io(e.to_string()))?;
-        let line = serde_json::to_string(&receipt).map_err(|e| ReceiptError::Parse(e.to_string()))?;
-        writeln!(file, "{line}").map_err(|e| ReceiptError::Io(e.to_string()))?;
-        *last = Some(receipt.chain_hash.clone());
+        let signature: Signature = self.signing_key.sign(receipt.chain_hash.as_bytes());
+        receipt.signature = BASE64.encode(signature.to_bytes());
+        receipt.signer_key_id = self.key_id.clone();
+
+        let plaintext = serde_json::to_vec(&receipt).map_err(|e| ReceiptError::Parse(e.to_string()))?;
+        let record = match &self.cipher {
+            Some(cipher) => cipher.seal(state.next_position, &plaintext)?,
+            None => plaintext,
+        };
+        self.backend.append(state.next_position, &record).await?;
+
+        state.last_hash = Some(receipt.chain_hash);
+        state.next_position += 1;
         Ok(())
     }
+
+    async fn read_all(&self) -> Result<Vec<Receipt>, ReceiptError> {
+        decode_records(self.backend.as_ref(), self.cipher.as_ref()).await
+    }
+
+    /// Incremental sync patterned on CalDAV `sync-collection`: returns every receipt appended
+    /// after `since` (or the whole log, if `since` is `None`) plus a fresh [`SyncToken`]
+    /// encoding the new chain position. Returns [`SyncResult::ResyncRequired`] if `since` doesn't
+    /// match the receipt actually at that position today.
+    ///
+    /// Named `sync_page` (not `sync`) to leave that name for [`ReceiptLog::sync`], the
+    /// peer-to-peer anti-entropy reconciliation this request's sibling request asked for by that
+    /// exact name — the two APIs serve different clients (a paginating HTTP reader vs. another
+    /// `ReceiptLog` replica) and take unrelated argument types, so there's no good overload here.
+    pub async fn sync_page(&self, since: Option<&SyncToken>) -> Result<SyncResult, ReceiptError> {
+        let all = self.read_all().await?;
+
+        let start = match since {
+            None => 0,
+            Some(token) if token.position == 0 => {
+                if !token.chain_hash.is_empty() {
+                    return Ok(SyncResult::ResyncRequired);
+                }
+                0
+            }
+            Some(token) => {
+                let anchor = all.get(token.position as usize - 1);
+                match anchor {
+                    Some(r) if r.chain_hash == token.chain_hash => token.position as usize,
+                    _ => return Ok(SyncResult::ResyncRequired),
+                }
+            }
+        };
+
+        let receipts = all.get(start..).unwrap_or_default().to_vec();
+        let next_token = SyncToken {
+            position: all.len() as u64,
+            chain_hash: all.last().map(|r| r.chain_hash.clone()).unwrap_or_default(),
+        };
+        Ok(SyncResult::Ok {
+            receipts,
+            next_token,
+        })
+    }
+
+    /// Walks the whole chain and returns the position of the first broken `prev_hash`/
+    /// `chain_hash` link, if any. Unlike [`verify_log`] this doesn't check signatures (so it
+    /// works without a verifying-key set) and reads through `backend`/`cipher` rather than a
+    /// fixed local path.
+    pub async fn verify_chain(&self) -> Result<ChainVerification, ReceiptError> {
+        let all = self.read_all().await?;
+        let mut prev: Option<String> = None;
+        for (position, receipt) in all.iter().enumerate() {
+            if let Some(reason) = chain_link_error(receipt, &prev) {
+                return Ok(ChainVerification::Broken {
+                    position: position as u64,
+                    reason,
+                });
+            }
+            prev = Some(receipt.chain_hash.clone());
+        }
+        Ok(ChainVerification::Ok)
+    }
+}
+
+/// Result of [`ReceiptLog::verify_chain`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainVerification {
+    Ok,
+    Broken { position: u64, reason: String },
 }
 
-pub fn verify_log(path: impl AsRef<Path>) -> Result<(), ReceiptError> {
+/// `Some(reason)` if `receipt` doesn't correctly follow `prev` in the chain; shared by
+/// [`ReceiptLog::verify_chain`] and [`verify_log`] so the two checks can't drift apart.
+fn chain_link_error(receipt: &Receipt, prev: &Option<String>) -> Option<String> {
+    if receipt.prev_hash != *prev {
+        return Some("prev_hash mismatch".into());
+    }
+    if receipt.chain_hash != hash_receipt(receipt) {
+        return Some("chain_hash invalid".into());
+    }
+    None
+}
+
+/// Read every record from `backend`, decrypting with `cipher` first if the log is encrypted.
+async fn decode_records(
+    backend: &dyn ReceiptBackend,
+    cipher: Option<&ReceiptCipher>,
+) -> Result<Vec<Receipt>, ReceiptError> {
+    backend
+        .read_all()
+        .await?
+        .into_iter()
+        .enumerate()
+        .map(|(position, bytes)| {
+            let plaintext = match cipher {
+                Some(cipher) => cipher.open(position as u64, &bytes)?,
+                None => bytes,
+            };
+            serde_json::from_slice(&plaintext).map_err(|e| ReceiptError::Parse(e.to_string()))
+        })
+        .collect()
+}
+
+/// Verify every line's chain linkage and signature.
+///
+/// `keys` maps `signer_key_id` -> public key, so rotating the signing key only requires adding
+/// its public key to the map under a new id; older lines keep verifying against their original
+/// key. A line whose signature fails, or whose `signer_key_id` is not in `keys`, is rejected.
+///
+/// NOTE: this does not and cannot recompute `input_hash`/`output_hash` from the original
+/// input/output payload — `Receipt` only ever stores the hash, never the payload itself — so
+/// those two fields are only checked for the right *shape* (see [`check_hash_field_shape`]).
+/// A receipt whose hash was computed over the wrong value, or never matched anything at all,
+/// still passes this check as long as it looks like a canonical hash; only `chain_hash` and its
+/// signature are actually cryptographically verified against real receipt content.
+pub fn verify_log(path: impl AsRef<Path>, keys: &HashMap<String, VerifyingKey>) -> Result<(), ReceiptError> {
     let file = File::open(path.as_ref()).map_err(|e| ReceiptError::Io(e.to_string()))?;
     let reader = BufReader::new(file);
     let mut prev: Option<String> = None;
     for line in reader.lines() {
         let line = line.map_err(|e| ReceiptError::Io(e.to_string()))?;
+        // Each line is base64(record), the framing `LocalFileBackend` writes.
+        let line = BASE64
+            .decode(line.trim())
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(|| ReceiptError::Parse("corrupt receipt log line".into()))?;
         let receipt: Receipt = serde_json::from_str(&line).map_err(|e| ReceiptError::Parse(e.to_string()))?;
-        if receipt.prev_hash != prev {
-            return Err(ReceiptError::Parse("hash chain mismatch".into()));
-        }
-        let expected = hash_receipt(&receipt);
-        if receipt.chain_hash != expected {
-            return Err(ReceiptError::Parse("chain hash invalid".into()));
+        if let Some(reason) = chain_link_error(&receipt, &prev) {
+            return Err(ReceiptError::Parse(reason));
         }
+
+        let key = keys
+            .get(&receipt.signer_key_id)
+            .ok_or_else(|| ReceiptError::Parse(format!("unknown signer key id '{}'", receipt.signer_key_id)))?;
+        let sig_bytes = BASE64
+            .decode(&receipt.signature)
+            .map_err(|e| ReceiptError::Parse(format!("invalid signature encoding: {e}")))?;
+        let sig_bytes: [u8; 64] = sig_bytes
+            .try_into()
+            .map_err(|_| ReceiptError::Parse("signature must be 64 bytes".into()))?;
+        let signature = Signature::from_bytes(&sig_bytes);
+        key.verify(receipt.chain_hash.as_bytes(), &signature)
+            .map_err(|_| ReceiptError::Parse("signature verification failed".into()))?;
+
+        check_hash_field_shape("input_hash", &receipt.input_hash)?;
+        check_hash_field_shape("output_hash", &receipt.output_hash)?;
+
         prev = Some(receipt.chain_hash);
     }
     Ok(())
 }
 
+/// `input_hash`/`output_hash` are meant to be `canonical_hash` output, hex-encoded: 32 bytes, 64
+/// hex digits. A blank value (no payload to hash for this action, e.g. the egress gate's
+/// `/fetch`) is also accepted; anything else means a producer hashed something other than a
+/// canonical encoding, so downstream cross-language verification can't be trusted.
+///
+/// This only checks the field *looks like* a canonical hash — it cannot recompute and compare
+/// against the original payload, since `Receipt` doesn't persist one (see the note on
+/// [`verify_log`]).
+fn check_hash_field_shape(field: &str, value: &str) -> Result<(), ReceiptError> {
+    if value.is_empty() {
+        return Ok(());
+    }
+    if value.len() != 64 || !value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ReceiptError::Parse(format!(
+            "{field} is not a 32-byte canonical hash: '{value}'"
+        )));
+    }
+    Ok(())
+}
+
+/// Wall-clock millis for [`AcceptStamp`]s; only needs to be locally monotonic-ish, not precise —
+/// ties are broken by `replica_id` and exact concurrent accepts on one replica by insertion order.
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 fn hash_receipt(receipt: &Receipt) -> String {
     let mut h = Sha256::new();
     h.update(receipt.receipt_id.to_string());
@@ -88,6 +508,9 @@ fn hash_receipt(receipt: &Receipt) -> String {
     if let Some(id) = receipt.capability_id {
         h.update(id.to_string());
     }
+    if let Some(check) = &receipt.capability_check {
+        h.update(check);
+    }
     h.update(&receipt.input_hash);
     h.update(&receipt.output_hash);
     if let Some(prev) = &receipt.prev_hash {
@@ -96,19 +519,64 @@ fn hash_receipt(receipt: &Receipt) -> String {
     format!("{:x}", h.finalize())
 }
 
-fn read_last_hash(path: &Path) -> Result<Option<String>, ReceiptError> {
-    if !path.exists() {
-        return Ok(None);
+/// Decode a base64-encoded 32-byte Ed25519 seed into a signing key.
+pub fn decode_signing_key(base64_seed: &str) -> Result<SigningKey, ReceiptError> {
+    let bytes = BASE64
+        .decode(base64_seed)
+        .map_err(|e| ReceiptError::Parse(format!("invalid signing key encoding: {e}")))?;
+    let seed: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| ReceiptError::Parse("signing key seed must be 32 bytes".into()))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Decode a base64-encoded 32-byte Ed25519 public key.
+pub fn decode_verifying_key(base64_key: &str) -> Result<VerifyingKey, ReceiptError> {
+    let bytes = BASE64
+        .decode(base64_key)
+        .map_err(|e| ReceiptError::Parse(format!("invalid verifying key encoding: {e}")))?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| ReceiptError::Parse("verifying key must be 32 bytes".into()))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| ReceiptError::Parse(format!("invalid verifying key: {e}")))
+}
+
+/// Load `(signing_key, key_id)` from the `key_var`/`id_var` env vars (base64 seed + plain id),
+/// generating an ephemeral signing key under id `"ephemeral"` when `key_var` is unset or
+/// unparsable. An ephemeral key is fine for local dev but means receipts signed this run cannot
+/// be verified after a restart.
+pub fn signing_key_from_env(key_var: &str, id_var: &str) -> (SigningKey, String) {
+    match std::env::var(key_var) {
+        Ok(b64) => match decode_signing_key(&b64) {
+            Ok(key) => (key, std::env::var(id_var).unwrap_or_else(|_| "default".into())),
+            Err(e) => {
+                eprintln!("{key_var} is set but invalid ({e}); generating an ephemeral signing key");
+                (SigningKey::generate(&mut OsRng), "ephemeral".into())
+            }
+        },
+        Err(_) => {
+            eprintln!("{key_var} not set; generating an ephemeral signing key (receipts won't verify across restarts)");
+            (SigningKey::generate(&mut OsRng), "ephemeral".into())
+        }
     }
-    let file = File::open(path).map_err(|e| ReceiptError::Io(e.to_string()))?;
-    let reader = BufReader::new(file);
-    let mut last: Option<String> = None;
-    for line in reader.lines() {
-        let line = line.map_err(|e| ReceiptError::Io(e.to_string()))?;
-        let receipt: Receipt = serde_json::from_str(&line).map_err(|e| ReceiptError::Parse(e.to_string()))?;
-        last = Some(receipt.chain_hash);
+}
+
+/// Parse a verification key file: one `key_id=base64_public_key` pair per line, blank lines and
+/// `#`-prefixed comments ignored. Used by `goni receipts verify` to build the `keys` map.
+pub fn load_verifying_keys(path: impl AsRef<Path>) -> Result<HashMap<String, VerifyingKey>, ReceiptError> {
+    let content = std::fs::read_to_string(path.as_ref()).map_err(|e| ReceiptError::Io(e.to_string()))?;
+    let mut keys = HashMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (id, b64) = line
+            .split_once('=')
+            .ok_or_else(|| ReceiptError::Parse(format!("malformed key line: '{line}'")))?;
+        keys.insert(id.trim().to_string(), decode_verifying_key(b64.trim())?);
     }
-    Ok(last)
+    Ok(keys)
 }
 
 #[cfg(test)]
@@ -116,23 +584,315 @@ mod tests {
     use super::*;
     use std::fs;
 
-    #[test]
-    fn receipt_chain_verifies() {
-        let path = "target/test_receipts.jsonl";
-        let _ = fs::remove_file(path);
-        let log = ReceiptLog::open(path).unwrap();
-        let r1 = Receipt {
+    fn new_receipt() -> Receipt {
+        Receipt {
             receipt_id: Uuid::new_v4(),
             timestamp: "t1".into(),
             action_type: "demo".into(),
             policy_decision: "allow".into(),
             capability_id: None,
-            input_hash: "a".into(),
-            output_hash: "b".into(),
+            capability_check: None,
+            input_hash: hex::encode(canonical_hash(&serde_json::json!("a"))),
+            output_hash: hex::encode(canonical_hash(&serde_json::json!("b"))),
             prev_hash: None,
             chain_hash: "".into(),
+            signer_key_id: "".into(),
+            signature: "".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn receipt_chain_verifies() {
+        let path = "target/test_receipts.jsonl";
+        let _ = fs::remove_file(path);
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let mut keys = HashMap::new();
+        keys.insert("k1".to_string(), signing_key.verifying_key());
+
+        let log = ReceiptLog::open(path, signing_key, "k1").await.unwrap();
+        log.append(new_receipt()).await.unwrap();
+        verify_log(path, &keys).unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_signer_key_id() {
+        let path = "target/test_receipts_unknown_key.jsonl";
+        let _ = fs::remove_file(path);
+        let signing_key = SigningKey::generate(&mut OsRng);
+
+        let log = ReceiptLog::open(path, signing_key, "k1").await.unwrap();
+        log.append(new_receipt()).await.unwrap();
+
+        let err = verify_log(path, &HashMap::new()).unwrap_err();
+        assert!(matches!(err, ReceiptError::Parse(_)));
+    }
+
+    #[tokio::test]
+    async fn rejects_tampered_chain_hash() {
+        let path = "target/test_receipts_tampered.jsonl";
+        let _ = fs::remove_file(path);
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let mut keys = HashMap::new();
+        keys.insert("k1".to_string(), signing_key.verifying_key());
+
+        let log = ReceiptLog::open(path, signing_key, "k1").await.unwrap();
+        log.append(new_receipt()).await.unwrap();
+
+        // Each line is base64(record), not the raw record, so tampering has to decode, edit the
+        // JSON, then re-encode rather than string-replacing the file's raw bytes.
+        let lines: Vec<String> = fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let decoded = String::from_utf8(BASE64.decode(line).unwrap()).unwrap();
+                decoded.replace("\"action_type\":\"demo\"", "\"action_type\":\"tampered\"")
+            })
+            .map(|line| BASE64.encode(line.as_bytes()))
+            .collect();
+        fs::write(path, lines.join("\n") + "\n").unwrap();
+
+        assert!(verify_log(path, &keys).is_err());
+    }
+
+    #[tokio::test]
+    async fn sync_from_scratch_returns_everything() {
+        let path = "target/test_receipts_sync_scratch.jsonl";
+        let _ = fs::remove_file(path);
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let log = ReceiptLog::open(path, signing_key, "k1").await.unwrap();
+        log.append(new_receipt()).await.unwrap();
+        log.append(new_receipt()).await.unwrap();
+
+        let SyncResult::Ok { receipts, next_token } = log.sync_page(None).await.unwrap() else {
+            panic!("expected Ok");
+        };
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(next_token.position, 2);
+    }
+
+    #[tokio::test]
+    async fn sync_since_token_returns_only_new_receipts() {
+        let path = "target/test_receipts_sync_incremental.jsonl";
+        let _ = fs::remove_file(path);
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let log = ReceiptLog::open(path, signing_key, "k1").await.unwrap();
+        log.append(new_receipt()).await.unwrap();
+
+        let SyncResult::Ok { next_token, .. } = log.sync_page(None).await.unwrap() else {
+            panic!("expected Ok");
         };
-        log.append(r1).unwrap();
-        verify_log(path).unwrap();
+
+        log.append(new_receipt()).await.unwrap();
+        log.append(new_receipt()).await.unwrap();
+
+        let SyncResult::Ok { receipts, next_token: _ } = log.sync_page(Some(&next_token)).await.unwrap() else {
+            panic!("expected Ok");
+        };
+        assert_eq!(receipts.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn sync_with_stale_token_requires_resync() {
+        let path = "target/test_receipts_sync_stale.jsonl";
+        let _ = fs::remove_file(path);
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let log = ReceiptLog::open(path, signing_key, "k1").await.unwrap();
+        log.append(new_receipt()).await.unwrap();
+
+        let stale = SyncToken {
+            position: 1,
+            chain_hash: "not-the-real-hash".into(),
+        };
+        assert!(matches!(log.sync_page(Some(&stale)).await.unwrap(), SyncResult::ResyncRequired));
+    }
+
+    #[tokio::test]
+    async fn verify_chain_flags_first_broken_link() {
+        let path = "target/test_receipts_verify_chain.jsonl";
+        let _ = fs::remove_file(path);
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let log = ReceiptLog::open(path, signing_key, "k1").await.unwrap();
+        log.append(new_receipt()).await.unwrap();
+        log.append(new_receipt()).await.unwrap();
+        assert_eq!(log.verify_chain().await.unwrap(), ChainVerification::Ok);
+
+        // Each line is base64(record), not the raw record, so tampering has to decode, edit the
+        // JSON, then re-encode rather than string-replacing the file's raw bytes.
+        let lines: Vec<String> = fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let decoded = String::from_utf8(BASE64.decode(line).unwrap()).unwrap();
+                decoded.replace("\"action_type\":\"demo\"", "\"action_type\":\"tampered\"")
+            })
+            .map(|line| BASE64.encode(line.as_bytes()))
+            .collect();
+        fs::write(path, lines.join("\n") + "\n").unwrap();
+
+        let reopened = ReceiptLog::open(path, SigningKey::generate(&mut OsRng), "k1").await.unwrap();
+        assert_eq!(
+            reopened.verify_chain().await.unwrap(),
+            ChainVerification::Broken {
+                position: 0,
+                reason: "chain_hash invalid".into(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn encrypted_log_round_trips_and_rejects_without_the_key() {
+        let path = "target/test_receipts_encrypted.jsonl";
+        let _ = fs::remove_file(path);
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let (cipher, key) = ReceiptCipher::generate();
+        let backend: Arc<dyn ReceiptBackend> = Arc::new(LocalFileBackend::new(path));
+
+        let log = ReceiptLog::with_backend(backend.clone(), signing_key, "k1", Some(cipher))
+            .await
+            .unwrap();
+        log.append(new_receipt()).await.unwrap();
+
+        // Reopening with the same key recovers the chain (and its length/tail hash) correctly.
+        let reopened = ReceiptLog::with_backend(
+            backend.clone(),
+            SigningKey::generate(&mut OsRng),
+            "k2",
+            Some(ReceiptCipher::new(key)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(reopened.verify_chain().await.unwrap(), ChainVerification::Ok);
+
+        // On disk it's ciphertext, not the JSON `Receipt` shape.
+        let raw = fs::read_to_string(path).unwrap();
+        assert!(!raw.contains("\"action_type\""));
+
+        // Without the key, records decode to noise rather than silently passing.
+        let wrong_key_cipher = ReceiptCipher::generate().0;
+        let err = ReceiptLog::with_backend(backend, SigningKey::generate(&mut OsRng), "k3", Some(wrong_key_cipher))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ReceiptError::Crypto(_)));
+    }
+
+    #[test]
+    fn sync_token_round_trips_through_encode_decode() {
+        let token = SyncToken {
+            position: 7,
+            chain_hash: "abc123".into(),
+        };
+        let decoded = SyncToken::decode(&token.encode()).unwrap();
+        assert_eq!(decoded, token);
+    }
+
+    #[tokio::test]
+    async fn secondary_accept_stays_tentative_after_reconciling() {
+        let primary_path = "target/test_receipts_bayou_primary.jsonl";
+        let secondary_path = "target/test_receipts_bayou_secondary.jsonl";
+        let _ = fs::remove_file(primary_path);
+        let _ = fs::remove_file(secondary_path);
+
+        let primary = ReceiptLog::open(primary_path, SigningKey::generate(&mut OsRng), "k1")
+            .await
+            .unwrap();
+        let secondary = ReceiptLog::with_role(
+            Arc::new(LocalFileBackend::new(secondary_path)),
+            SigningKey::generate(&mut OsRng),
+            "k2",
+            None,
+            ReplicaRole::Secondary { replica_id: 7 },
+        )
+        .await
+        .unwrap();
+
+        secondary.accept(new_receipt()).await.unwrap();
+        assert_eq!(secondary.read_all().await.unwrap().len(), 0);
+        assert_eq!(secondary.tentative.lock().await.len(), 1);
+
+        // Primary commits its own write, then pulls the secondary's tentative write in by
+        // reconciling; nothing gets promoted until the primary itself commits it.
+        primary.append(new_receipt()).await.unwrap();
+        secondary.sync(&primary).await.unwrap();
+        assert_eq!(secondary.read_all().await.unwrap().len(), 1);
+        assert_eq!(secondary.tentative.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn two_secondaries_converge_after_reconciling_through_the_primary() {
+        let primary_path = "target/test_receipts_bayou_converge_primary.jsonl";
+        let a_path = "target/test_receipts_bayou_converge_a.jsonl";
+        let b_path = "target/test_receipts_bayou_converge_b.jsonl";
+        let _ = fs::remove_file(primary_path);
+        let _ = fs::remove_file(a_path);
+        let _ = fs::remove_file(b_path);
+
+        let primary = ReceiptLog::open(primary_path, SigningKey::generate(&mut OsRng), "k1")
+            .await
+            .unwrap();
+        let a = ReceiptLog::with_role(
+            Arc::new(LocalFileBackend::new(a_path)),
+            SigningKey::generate(&mut OsRng),
+            "ka",
+            None,
+            ReplicaRole::Secondary { replica_id: 1 },
+        )
+        .await
+        .unwrap();
+        let b = ReceiptLog::with_role(
+            Arc::new(LocalFileBackend::new(b_path)),
+            SigningKey::generate(&mut OsRng),
+            "kb",
+            None,
+            ReplicaRole::Secondary { replica_id: 2 },
+        )
+        .await
+        .unwrap();
+
+        a.accept(new_receipt()).await.unwrap();
+        b.accept(new_receipt()).await.unwrap();
+
+        // a reconciles with the primary first (pulling nothing new), which leaves both holding a's
+        // tentative write; the primary then reconciles with b, leaving both holding both writes;
+        // the last two syncs hand that merged suffix back to a and b.
+        a.sync(&primary).await.unwrap();
+        primary.sync(&b).await.unwrap();
+        a.sync(&primary).await.unwrap();
+        b.sync(&primary).await.unwrap();
+
+        let a_tentative_ids: std::collections::HashSet<Uuid> =
+            a.tentative.lock().await.iter().map(|op| op.receipt.receipt_id).collect();
+        let b_tentative_ids: std::collections::HashSet<Uuid> =
+            b.tentative.lock().await.iter().map(|op| op.receipt.receipt_id).collect();
+        assert_eq!(a_tentative_ids, b_tentative_ids);
+        assert_eq!(a_tentative_ids.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn commit_oldest_tentative_assigns_a_csn_on_the_primary() {
+        let path = "target/test_receipts_bayou_commit_oldest.jsonl";
+        let _ = fs::remove_file(path);
+        let primary = ReceiptLog::with_role(
+            Arc::new(LocalFileBackend::new(path)),
+            SigningKey::generate(&mut OsRng),
+            "k1",
+            None,
+            ReplicaRole::Primary,
+        )
+        .await
+        .unwrap();
+
+        // `accept` on a primary commits straight away, so stage a tentative op directly to
+        // exercise `commit_oldest_tentative` rather than going through `accept`.
+        primary.tentative.lock().await.push(TentativeOp {
+            stamp: AcceptStamp { accept_timestamp: 1, replica_id: 0 },
+            receipt: new_receipt(),
+            dep_check: default_dep_check(),
+            merge: default_merge(),
+        });
+
+        assert!(primary.commit_oldest_tentative().await.unwrap());
+        assert_eq!(primary.read_all().await.unwrap().len(), 1);
+        assert!(primary.tentative.lock().await.is_empty());
+        assert!(!primary.commit_oldest_tentative().await.unwrap());
     }
 }
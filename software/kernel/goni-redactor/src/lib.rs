@@ -1,20 +1,232 @@
-use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
 use goni_classifier::DataClass;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// A replacement span emitted by a [`RedactionRule`], analogous to a linter's autofix edit.
+#[derive(Clone, Debug)]
+pub struct RedactionSpan {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+    pub rule_name: String,
+}
+
+/// Scans input text and emits the spans it wants replaced.
+pub trait RedactionRule: Send + Sync {
+    fn name(&self) -> &str;
+    fn scan(&self, text: &str) -> Vec<RedactionSpan>;
+}
+
+/// Regex-backed rule: every match becomes a span replaced with a fixed placeholder.
+pub struct RegexRule {
+    name: String,
+    pattern: Regex,
+    placeholder: String,
+}
+
+impl RegexRule {
+    pub fn new(name: impl Into<String>, pattern: &str, placeholder: impl Into<String>) -> Result<Self, regex::Error> {
+        Ok(Self {
+            name: name.into(),
+            pattern: Regex::new(pattern)?,
+            placeholder: placeholder.into(),
+        })
+    }
+}
+
+impl RedactionRule for RegexRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn scan(&self, text: &str) -> Vec<RedactionSpan> {
+        self.pattern
+            .find_iter(text)
+            .map(|m| RedactionSpan {
+                start: m.start(),
+                end: m.end(),
+                replacement: self.placeholder.clone(),
+                rule_name: self.name.clone(),
+            })
+            .collect()
+    }
+}
 
+/// Metadata mirroring the `RedactionProfiles` table: which ruleset applies and how strict it is.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RedactionProfile {
+    pub mode: String,
+    pub ruleset_hash: [u8; 32],
     pub fail_closed: bool,
 }
 
-pub fn redact(text: &str, class: DataClass, profile: &RedactionProfile) -> Result<String, String> {
-    match class {
-        DataClass::Secret => {
-            if profile.fail_closed {
-                Ok("[REDACTED]".into())
-            } else {
-                Err("secret_requires_redaction".into())
+/// Result of running the engine, shaped to populate a `RedactionEvents` row.
+#[derive(Clone, Debug)]
+pub struct RedactionOutcome {
+    pub output: String,
+    pub before_hash: [u8; 32],
+    pub after_hash: [u8; 32],
+    /// Per-rule hit counts, for `RedactionEvents.redaction_summary`.
+    pub redaction_summary: HashMap<String, u32>,
+}
+
+/// Runs a fixed set of [`RedactionRule`]s over text and resolves overlapping spans.
+pub struct RedactionEngine {
+    rules: Vec<Box<dyn RedactionRule>>,
+}
+
+impl RedactionEngine {
+    pub fn new(rules: Vec<Box<dyn RedactionRule>>) -> Self {
+        Self { rules }
+    }
+
+    /// Built-in ruleset: emails, bearer tokens, and UUID-shaped ids.
+    pub fn with_default_rules() -> Self {
+        let rules: Vec<Box<dyn RedactionRule>> = vec![
+            Box::new(
+                RegexRule::new(
+                    "email",
+                    r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}",
+                    "[REDACTED_EMAIL]",
+                )
+                .expect("valid email regex"),
+            ),
+            Box::new(
+                RegexRule::new(
+                    "token",
+                    r"(?i)\b(?:bearer|sk-|api[_-]?key)[A-Za-z0-9._=-]*",
+                    "[REDACTED_TOKEN]",
+                )
+                .expect("valid token regex"),
+            ),
+            Box::new(
+                RegexRule::new(
+                    "uuid",
+                    r"[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}",
+                    "[REDACTED_ID]",
+                )
+                .expect("valid uuid regex"),
+            ),
+        ];
+        Self::new(rules)
+    }
+
+    /// Hash of the rule names, for `RedactionProfiles.ruleset_hash`.
+    pub fn ruleset_hash(&self) -> [u8; 32] {
+        let mut names: Vec<&str> = self.rules.iter().map(|r| r.name()).collect();
+        names.sort_unstable();
+        let mut h = Sha256::new();
+        for name in names {
+            h.update(name.as_bytes());
+        }
+        h.finalize().into()
+    }
+
+    /// Resolve overlapping spans by earliest-start / longest-match priority, then apply the
+    /// surviving edits left-to-right.
+    fn resolve_and_apply(&self, text: &str, mut spans: Vec<RedactionSpan>) -> (String, HashMap<String, u32>) {
+        spans.sort_by(|a, b| a.start.cmp(&b.start).then((b.end - b.start).cmp(&(a.end - a.start))));
+
+        let mut accepted: Vec<RedactionSpan> = Vec::new();
+        let mut cursor = 0usize;
+        for span in spans {
+            if span.start < cursor {
+                continue; // overlaps an already-accepted (higher-priority) span
             }
+            cursor = span.end;
+            accepted.push(span);
         }
-        _ => Ok(text.to_string()),
+
+        let mut summary: HashMap<String, u32> = HashMap::new();
+        let mut output = String::with_capacity(text.len());
+        let mut pos = 0usize;
+        for span in &accepted {
+            output.push_str(&text[pos..span.start]);
+            output.push_str(&span.replacement);
+            pos = span.end;
+            *summary.entry(span.rule_name.clone()).or_insert(0) += 1;
+        }
+        output.push_str(&text[pos..]);
+
+        (output, summary)
+    }
+
+    /// Run every rule over `text`, resolve overlaps, and apply the edits. A `Secret`-classified
+    /// text that no rule matched still errors unless `profile.fail_closed` is false, which
+    /// permits pass-through of the (unmatched) text as-is.
+    pub fn redact(
+        &self,
+        text: &str,
+        class: DataClass,
+        profile: &RedactionProfile,
+    ) -> Result<RedactionOutcome, String> {
+        let spans: Vec<RedactionSpan> = self.rules.iter().flat_map(|rule| rule.scan(text)).collect();
+        let any_hit = !spans.is_empty();
+
+        if class == DataClass::Secret && !any_hit && profile.fail_closed {
+            return Err("secret_requires_redaction".into());
+        }
+
+        let (output, redaction_summary) = self.resolve_and_apply(text, spans);
+
+        Ok(RedactionOutcome {
+            before_hash: Sha256::digest(text.as_bytes()).into(),
+            after_hash: Sha256::digest(output.as_bytes()).into(),
+            output,
+            redaction_summary,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(fail_closed: bool) -> RedactionProfile {
+        RedactionProfile {
+            mode: "default".into(),
+            ruleset_hash: [0u8; 32],
+            fail_closed,
+        }
+    }
+
+    #[test]
+    fn redacts_email_and_token() {
+        let engine = RedactionEngine::with_default_rules();
+        let outcome = engine
+            .redact("contact me@example.com, key=sk-abc123", DataClass::Public, &profile(true))
+            .unwrap();
+        assert!(outcome.output.contains("[REDACTED_EMAIL]"));
+        assert!(outcome.output.contains("[REDACTED_TOKEN]"));
+        assert_eq!(outcome.redaction_summary.get("email"), Some(&1));
+    }
+
+    #[test]
+    fn secret_without_match_fails_closed() {
+        let engine = RedactionEngine::with_default_rules();
+        let result = engine.redact("plain secret text", DataClass::Secret, &profile(true));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn secret_without_match_passes_through_when_not_fail_closed() {
+        let engine = RedactionEngine::with_default_rules();
+        let outcome = engine
+            .redact("plain secret text", DataClass::Secret, &profile(false))
+            .unwrap();
+        assert_eq!(outcome.output, "plain secret text");
+    }
+
+    #[test]
+    fn overlapping_spans_prefer_longest_match() {
+        let engine = RedactionEngine::new(vec![
+            Box::new(RegexRule::new("short", "abc", "[S]").unwrap()),
+            Box::new(RegexRule::new("long", "abcdef", "[L]").unwrap()),
+        ]);
+        let outcome = engine.redact("abcdef", DataClass::Public, &profile(true)).unwrap();
+        assert_eq!(outcome.output, "[L]");
     }
 }
@@ -1,9 +1,12 @@
+use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use goni_types::{ContextSelection, ModelTier};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub struct RoutingDecision {
@@ -50,9 +53,14 @@ impl Router for NullRouter {
     }
 }
 
-#[derive(Clone, Debug)]
+/// Routes on a [`RouterConfig`] that can be hot-reloaded from disk.
+///
+/// `cfg` is swapped atomically behind an `ArcSwap` so a reload never blocks or invalidates an
+/// in-flight `decide` call: readers always see either the config from before the reload or the
+/// config from after it, never a partially-applied one.
+#[derive(Clone)]
 pub struct ConfigRouter {
-    cfg: RouterConfig,
+    cfg: Arc<ArcSwap<RouterConfig>>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -78,15 +86,72 @@ struct LocalFirstConfig {
 }
 
 impl ConfigRouter {
+    /// Read `council.yaml` once at startup. The config is never reloaded; use [`Self::watch`]
+    /// when routing-policy changes need to take effect without restarting the orchestrator.
     pub fn from_path(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let cfg = Self::load_config(path.as_ref())?;
+        Ok(Self {
+            cfg: Arc::new(ArcSwap::from_pointee(cfg)),
+        })
+    }
+
+    /// Like [`Self::from_path`], but also spawns a `notify` watcher on `path`: every time the
+    /// file changes, the new YAML is parsed and, only if that succeeds, atomically swapped in
+    /// via `ArcSwap`. A parse failure leaves the previously active config in place and is logged
+    /// rather than propagated, since a malformed edit on disk shouldn't take the router down.
+    ///
+    /// The spawned thread owns the watcher (and with it the event channel's sender), so it runs
+    /// until the process exits; dropping the returned `ConfigRouter` does not stop it.
+    pub fn watch(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let cfg = Arc::new(ArcSwap::from_pointee(Self::load_config(&path)?));
+
+        let swap = cfg.clone();
+        let watch_path = path.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher: RecommendedWatcher = notify::recommended_watcher(tx)?;
+        watcher.watch(&watch_path, RecursiveMode::NonRecursive)?;
+
+        std::thread::spawn(move || {
+            // Owning the watcher here keeps it (and its OS-level subscription) alive for as
+            // long as this thread runs; dropping it would silently stop delivering events.
+            let _watcher = watcher;
+            for event in rx {
+                let Ok(event) = event else { continue; };
+                if !event.kind.is_modify() && !event.kind.is_create() {
+                    continue;
+                }
+                match Self::load_config(&watch_path) {
+                    Ok(new_cfg) => {
+                        swap.store(Arc::new(new_cfg));
+                        eprintln!(
+                            "event=config_router_reload status=ok path={}",
+                            watch_path.display()
+                        );
+                    }
+                    Err(e) => {
+                        eprintln!(
+                            "event=config_router_reload status=rejected path={} error={e:?}",
+                            watch_path.display()
+                        );
+                    }
+                }
+            }
+        });
+
+        Ok(Self { cfg })
+    }
+
+    fn load_config(path: &Path) -> anyhow::Result<RouterConfig> {
         let text = fs::read_to_string(path)?;
         let cfg: RouterConfig = serde_yaml::from_str(&text)?;
-        Ok(Self { cfg })
+        Ok(cfg)
     }
 
     fn choose_tier(&self, prompt: &str) -> ModelTier {
+        let cfg = self.cfg.load();
         let default = ModelTier::LocalSmall;
-        let Some(models) = self.cfg.models.get("interactive") else { return default; };
+        let Some(models) = cfg.models.get("interactive") else { return default; };
         let max_tokens = models.max_tokens.unwrap_or(256) as usize;
         if prompt.split_whitespace().count() > max_tokens {
             ModelTier::LocalLarge
@@ -113,3 +178,80 @@ impl Router for ConfigRouter {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    const SMALL_MAX_TOKENS: &str = r#"
+models:
+  interactive:
+    id: local-small
+    max_tokens: 4
+"#;
+
+    const LARGE_MAX_TOKENS: &str = r#"
+models:
+  interactive:
+    id: local-small
+    max_tokens: 100
+"#;
+
+    #[tokio::test]
+    async fn from_path_routes_on_the_loaded_config() {
+        let path = "target/test_router_from_path.yaml";
+        fs::write(path, SMALL_MAX_TOKENS).unwrap();
+
+        let router = ConfigRouter::from_path(path).unwrap();
+        let (decision, _) = router
+            .decide("one two three four five six", &ContextSelection { indices: Vec::new(), total_tokens: 0 })
+            .await;
+        assert_eq!(decision.chosen_tier, ModelTier::LocalLarge);
+    }
+
+    #[tokio::test]
+    async fn watch_reloads_config_on_file_change() {
+        let path = "target/test_router_watch.yaml";
+        fs::write(path, SMALL_MAX_TOKENS).unwrap();
+
+        let router = ConfigRouter::watch(path).unwrap();
+        let (decision, _) = router
+            .decide("one two three four five six", &ContextSelection { indices: Vec::new(), total_tokens: 0 })
+            .await;
+        assert_eq!(decision.chosen_tier, ModelTier::LocalLarge);
+
+        fs::write(path, LARGE_MAX_TOKENS).unwrap();
+        // `notify` delivers the change asynchronously on its own thread; poll briefly instead of
+        // assuming a fixed propagation delay.
+        let mut reloaded = false;
+        for _ in 0..50 {
+            thread::sleep(Duration::from_millis(20));
+            let (decision, _) = router
+                .decide("one two three four five six", &ContextSelection { indices: Vec::new(), total_tokens: 0 })
+                .await;
+            if decision.chosen_tier == ModelTier::LocalSmall {
+                reloaded = true;
+                break;
+            }
+        }
+        assert!(reloaded, "expected watch() to pick up the config change");
+    }
+
+    #[tokio::test]
+    async fn watch_keeps_previous_config_on_parse_failure() {
+        let path = "target/test_router_watch_bad_reload.yaml";
+        fs::write(path, SMALL_MAX_TOKENS).unwrap();
+
+        let router = ConfigRouter::watch(path).unwrap();
+        fs::write(path, "not: [valid, yaml for RouterConfig").unwrap();
+        thread::sleep(Duration::from_millis(100));
+
+        // The malformed rewrite should have been rejected, leaving the original config active.
+        let (decision, _) = router
+            .decide("one two three four five six", &ContextSelection { indices: Vec::new(), total_tokens: 0 })
+            .await;
+        assert_eq!(decision.chosen_tier, ModelTier::LocalLarge);
+    }
+}
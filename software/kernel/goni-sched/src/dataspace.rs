@@ -0,0 +1,132 @@
+//! Bridges `Tasks` facts asserted into a [`goni_dataspace::Dataspace`] into [`Scheduler::submit`]
+//! calls, so a scheduler can be driven by subscription instead of a caller calling `submit`
+//! directly (see `goni-cli`'s demo command for the end-to-end wiring).
+
+use std::sync::Arc;
+
+use goni_dataspace::{Dataspace, Event, FieldValue, ObserverId, Pattern};
+use goni_types::{BatchMeta, GoniBatch, TaskClass};
+
+use crate::{SchedError, Scheduler};
+
+fn class_from_queue_id(s: &str) -> Option<TaskClass> {
+    match s {
+        "interactive" => Some(TaskClass::Interactive),
+        "background" => Some(TaskClass::Background),
+        "maintenance" => Some(TaskClass::Maintenance),
+        _ => None,
+    }
+}
+
+/// Subscribes to `Tasks` facts in the `Queued` state, capturing `task_id`, `queue_id` (the task
+/// class), and `expected_cost_tokens`. Pass the returned [`ObserverId`] to [`submit_from_event`]
+/// to tell matching events apart from wakes delivered to other observers on the same dataspace.
+pub fn subscribe_queued_tasks(dataspace: &Dataspace) -> ObserverId {
+    dataspace.subscribe(
+        Pattern::new("Tasks")
+            .exact("state", FieldValue::Str("Queued".to_string()))
+            .capture("task_id", "task_id")
+            .capture("queue_id", "queue_id")
+            .capture("expected_cost_tokens", "expected_cost_tokens"),
+    )
+}
+
+/// Submits a batch to `scheduler` for the `Assert` half of a `subscribe_queued_tasks` wake;
+/// `Retract` events (a task leaving the `Queued` state some other way) aren't new submissions and
+/// are ignored. Returns `Ok(())` without submitting if `event` belongs to a different observer,
+/// or if its bindings don't carry a recognized `queue_id`.
+pub async fn submit_from_event(
+    scheduler: &dyn Scheduler,
+    observer: ObserverId,
+    event: &Event,
+) -> Result<(), SchedError> {
+    let Event::Assert { observer: fired, bindings } = event else {
+        return Ok(());
+    };
+    if *fired != observer {
+        return Ok(());
+    }
+    let Some(FieldValue::Str(queue_id)) = bindings.get("queue_id") else {
+        return Ok(());
+    };
+    let Some(class) = class_from_queue_id(queue_id) else {
+        return Ok(());
+    };
+    let est_tokens = match bindings.get("expected_cost_tokens") {
+        Some(FieldValue::Int(tokens)) => (*tokens).max(1) as usize,
+        _ => 1,
+    };
+    // Reuses the submitter's id (rather than minting a new one here) so a caller that's tracking
+    // this task by id — e.g. `GoniKernel`'s pending-response map — still recognizes the batch
+    // that comes back out of `Scheduler::next`.
+    let id = match bindings.get("task_id") {
+        Some(FieldValue::Str(s)) => s.parse().unwrap_or_else(|_| uuid::Uuid::new_v4()),
+        _ => uuid::Uuid::new_v4(),
+    };
+
+    let schema = Arc::new(arrow::datatypes::Schema::empty());
+    let data = Arc::new(arrow::record_batch::RecordBatch::new_empty(schema));
+    let batch = GoniBatch {
+        data,
+        meta: BatchMeta {
+            id,
+            class,
+            arrival_ts: std::time::Instant::now(),
+            est_tokens,
+            dequeue_ts: None,
+        },
+    };
+    scheduler.submit(batch).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryScheduler;
+
+    #[tokio::test]
+    async fn assert_event_submits_a_batch_of_the_bound_class() {
+        let dataspace = Dataspace::new();
+        let observer = subscribe_queued_tasks(&dataspace);
+
+        let task_id = uuid::Uuid::new_v4();
+        let mut fields = std::collections::BTreeMap::new();
+        fields.insert("state".to_string(), FieldValue::Str("Queued".to_string()));
+        fields.insert("task_id".to_string(), FieldValue::Str(task_id.to_string()));
+        fields.insert("queue_id".to_string(), FieldValue::Str("interactive".to_string()));
+        fields.insert("expected_cost_tokens".to_string(), FieldValue::Int(42));
+        let fact = goni_dataspace::Fact {
+            kind: "Tasks".to_string(),
+            plane: "Control".to_string(),
+            fields,
+            row: Arc::new(arrow::record_batch::RecordBatch::new_empty(Arc::new(
+                arrow::datatypes::Schema::empty(),
+            ))),
+        };
+
+        let events = dataspace.assert(fact);
+        assert_eq!(events.len(), 1);
+
+        let scheduler = InMemoryScheduler::new();
+        submit_from_event(&scheduler, observer, &events[0]).await.unwrap();
+
+        let batch = scheduler.next().await.expect("submission should have queued a batch");
+        assert_eq!(batch.meta.class, TaskClass::Interactive);
+        assert_eq!(batch.meta.est_tokens, 42);
+        assert_eq!(batch.meta.id, task_id);
+    }
+
+    #[tokio::test]
+    async fn retract_event_is_not_resubmitted() {
+        let dataspace = Dataspace::new();
+        let observer = subscribe_queued_tasks(&dataspace);
+        let event = Event::Retract {
+            observer,
+            bindings: Default::default(),
+        };
+
+        let scheduler = InMemoryScheduler::new();
+        submit_from_event(&scheduler, observer, &event).await.unwrap();
+        assert!(scheduler.next().await.is_none());
+    }
+}
@@ -1,17 +1,27 @@
 use std::collections::VecDeque;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use goni_types::{GoniBatch, TaskClass, BatchMeta};
 use tokio::sync::Mutex;
 use arrow::record_batch::RecordBatch;
 use arrow::datatypes::Schema;
+use uuid::Uuid;
+
+pub mod dataspace;
 
 /// Core scheduling interface.
 #[async_trait]
 pub trait Scheduler: Send + Sync {
     async fn submit(&self, batch: GoniBatch) -> Result<(), SchedError>;
     async fn next(&self) -> Option<GoniBatch>;
+
+    /// Report that a previously-dequeued batch finished: `tokens` actually emitted over
+    /// `duration` (measured from `BatchMeta::dequeue_ts`, i.e. pure service time, not queueing
+    /// delay). Schedulers that estimate a per-class service rate (see [`InMemoryScheduler`]) fold
+    /// this into their EMA; schedulers that don't can ignore it.
+    async fn report_complete(&self, id: Uuid, class: TaskClass, tokens: usize, duration: Duration);
 }
 
 #[derive(Debug)]
@@ -19,19 +29,38 @@ pub struct SchedError {
     pub message: String,
 }
 
-/// Simple in-memory MaxWeight-ish scheduler.
-/// For now we assume same service rate across classes; later you plug in EMA-based µ.
+/// Warm-up service-rate estimate (tokens/sec) a class starts at before it has completed anything,
+/// so an untested class isn't scored as infinitely slow (and starved) or infinitely fast
+/// (and allowed to hoard the server) on its first few batches.
+const WARMUP_SERVICE_RATE: f64 = 1.0;
+
+/// Default EMA smoothing factor for `Inner::service_rate`; see `InMemoryScheduler::with_alpha`.
+const DEFAULT_ALPHA: f64 = 0.2;
+
+/// MaxWeight scheduler: picks the nonempty queue maximizing `weight_i * queue_len_i * µ_i`, where
+/// µ_i is an EMA estimate of class i's measured service rate (tokens/sec). Weighting by µ_i
+/// (rather than plain `weight_i * queue_len_i`) is the MaxWeight-optimal rule once service rates
+/// differ across classes: a class whose measured throughput has collapsed is deprioritized
+/// automatically instead of being able to hoard the server on queue length alone.
 pub struct InMemoryScheduler {
     inner: Mutex<Inner>,
+    alpha: f64,
 }
 
 struct Inner {
     queues: [VecDeque<Arc<GoniBatch>>; 3],
     weights: [f64; 3], // w_int, w_bg, w_maint
+    service_rate: [f64; 3], // µ_int, µ_bg, µ_maint (tokens/sec EMA)
 }
 
 impl InMemoryScheduler {
     pub fn new() -> Self {
+        Self::with_alpha(DEFAULT_ALPHA)
+    }
+
+    /// `alpha` is the EMA smoothing factor in `µ_i ← alpha·(tokens/duration_secs) + (1-alpha)·µ_i`
+    /// — higher tracks recent throughput more closely, lower rides out noisy individual batches.
+    pub fn with_alpha(alpha: f64) -> Self {
         Self {
             inner: Mutex::new(Inner {
                 queues: [
@@ -40,11 +69,19 @@ impl InMemoryScheduler {
                     VecDeque::new(),
                 ],
                 weights: [1000.0, 10.0, 1.0],
+                service_rate: [WARMUP_SERVICE_RATE; 3],
             }),
+            alpha,
         }
     }
 }
 
+impl Default for InMemoryScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 fn idx_for(class: TaskClass) -> usize {
     match class {
         TaskClass::Interactive => 0,
@@ -65,7 +102,7 @@ impl Scheduler for InMemoryScheduler {
     async fn next(&self) -> Option<GoniBatch> {
         let mut inner = self.inner.lock().await;
 
-        // MaxWeight simplified: pick queue with largest w_i * Q_i
+        // MaxWeight: pick queue with largest w_i * Q_i * µ_i.
         let mut best_idx: Option<usize> = None;
         let mut best_score = f64::MIN;
 
@@ -74,20 +111,29 @@ impl Scheduler for InMemoryScheduler {
             if q_len == 0.0 {
                 continue;
             }
-            let score = inner.weights[i] * q_len;
+            let score = inner.weights[i] * q_len * inner.service_rate[i];
             if score > best_score {
                 best_score = score;
                 best_idx = Some(i);
             }
         }
 
-        if let Some(idx) = best_idx {
-            inner.queues[idx]
-                .pop_front()
-                .map(|arc| Arc::try_unwrap(arc).unwrap_or_else(|a| (*a).clone()))
-        } else {
-            None
+        let idx = best_idx?;
+        let mut batch = Arc::try_unwrap(inner.queues[idx].pop_front()?).unwrap_or_else(|a| (*a).clone());
+        batch.meta.dequeue_ts = Some(Instant::now());
+        Some(batch)
+    }
+
+    async fn report_complete(&self, _id: Uuid, class: TaskClass, tokens: usize, duration: Duration) {
+        let idx = idx_for(class);
+        let duration_secs = duration.as_secs_f64();
+        if duration_secs <= 0.0 {
+            return;
         }
+        let observed_rate = tokens as f64 / duration_secs;
+
+        let mut inner = self.inner.lock().await;
+        inner.service_rate[idx] = self.alpha * observed_rate + (1.0 - self.alpha) * inner.service_rate[idx];
     }
 }
 
@@ -132,11 +178,17 @@ impl Scheduler for QoSScheduler {
         for class in order {
             let idx = idx_for(class);
             if let Some(batch) = inner.queues[idx].pop_front() {
-                return Some(Arc::try_unwrap(batch).unwrap_or_else(|a| (*a).clone()));
+                let mut batch = Arc::try_unwrap(batch).unwrap_or_else(|a| (*a).clone());
+                batch.meta.dequeue_ts = Some(Instant::now());
+                return Some(batch);
             }
         }
         None
     }
+
+    /// No-op: `QoSScheduler` admits strictly by fixed per-class WIP limits, not by an estimated
+    /// service rate, so there's no per-class state here for completion reports to feed.
+    async fn report_complete(&self, _id: Uuid, _class: TaskClass, _tokens: usize, _duration: Duration) {}
 }
 
 #[cfg(test)]
@@ -154,6 +206,7 @@ mod tests {
                 class,
                 arrival_ts: std::time::Instant::now(),
                 est_tokens: 1,
+                dequeue_ts: None,
             },
         }
     }
@@ -174,5 +227,32 @@ mod tests {
         let res = sched.submit(dummy_batch(TaskClass::Background)).await;
         assert!(res.is_err());
     }
+
+    #[tokio::test]
+    async fn next_stamps_dequeue_ts() {
+        let sched = InMemoryScheduler::new();
+        sched.submit(dummy_batch(TaskClass::Interactive)).await.unwrap();
+
+        let batch = sched.next().await.expect("should pop a batch");
+        assert!(batch.meta.dequeue_ts.is_some());
+    }
+
+    #[tokio::test]
+    async fn slow_class_is_deprioritized_after_reporting_completion() {
+        // Weights alone would always favor Interactive (w=1000 vs w=10), so report enough slow
+        // completions that its EMA-estimated rate collapses far below Background's to flip the
+        // MaxWeight comparison: 1000 * 1 * µ_int < 10 * 1 * µ_bg.
+        let sched = InMemoryScheduler::with_alpha(1.0);
+        let id = Uuid::new_v4();
+        sched
+            .report_complete(id, TaskClass::Interactive, 1, Duration::from_secs(1000))
+            .await;
+
+        sched.submit(dummy_batch(TaskClass::Background)).await.unwrap();
+        sched.submit(dummy_batch(TaskClass::Interactive)).await.unwrap();
+
+        let first = sched.next().await.expect("should pop a batch");
+        assert_eq!(first.meta.class, TaskClass::Background);
+    }
 }
 
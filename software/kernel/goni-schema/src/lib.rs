@@ -348,6 +348,34 @@ pub mod generated {
             }
         }
     }
+
+    /// Every `define_tables!` table's logical name, owning plane, and Arrow schema, in
+    /// declaration order — lets a `DataPlane` (see `goni_store::DataFusionDataPlane`) register
+    /// the whole Spine up front instead of learning a table's shape the first time it's appended.
+    pub fn table_registry() -> Vec<(&'static str, Plane, ::arrow::datatypes::Schema)> {
+        vec![
+            ("Docs", Plane::Knowledge, Docs::schema()),
+            ("Chunks", Plane::Knowledge, Chunks::schema()),
+            ("Embeddings", Plane::Knowledge, Embeddings::schema()),
+            ("Requests", Plane::Control, Requests::schema()),
+            ("Tasks", Plane::Control, Tasks::schema()),
+            ("AuditRecords", Plane::Control, AuditRecords::schema()),
+            ("CapabilityTokens", Plane::Control, CapabilityTokens::schema()),
+            ("RedactionProfiles", Plane::Control, RedactionProfiles::schema()),
+            ("RedactionEvents", Plane::Control, RedactionEvents::schema()),
+            ("AgentManifests", Plane::Control, AgentManifests::schema()),
+            ("Prompts", Plane::Context, Prompts::schema()),
+            ("ContextItems", Plane::Context, ContextItems::schema()),
+            ("StateSnapshots", Plane::Knowledge, StateSnapshots::schema()),
+            ("StateDeltas", Plane::Knowledge, StateDeltas::schema()),
+            ("LatentSummaries", Plane::Knowledge, LatentSummaries::schema()),
+            ("MemoryEntries", Plane::Knowledge, MemoryEntries::schema()),
+            ("LlmCalls", Plane::Execution, LlmCalls::schema()),
+            ("PlatformSignals", Plane::Execution, PlatformSignals::schema()),
+            ("PlatformCapabilities", Plane::Execution, PlatformCapabilities::schema()),
+            ("Metrics", Plane::Execution, Metrics::schema()),
+        ]
+    }
 }
 
 pub use generated::*;
@@ -0,0 +1,470 @@
+//! A durable, bayou-style append log `DataPlane`, as an alternative to
+//! [`InMemorySpineDataPlane`] for tables that need to survive restarts and merge across
+//! replicas (see `docs/specs` for the CRDT/optimistic-replication design).
+//!
+//! Every append becomes a [`BayouOp`] carrying a Lamport timestamp, a dependency-check
+//! predicate, and a merge function to run if that predicate fails. The materialized state of
+//! a table is the replay, in timestamp order, of a committed prefix followed by a tentative
+//! suffix. Remote operations that arrive out of order are spliced into the tentative suffix at
+//! their timestamp position and the suffix is replayed forward on every read, so conflicting
+//! appends resolve the same way regardless of arrival order.
+
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use arrow::compute::concat_batches;
+use arrow::ipc::{reader::StreamReader, writer::StreamWriter};
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+
+use crate::{ArrowBatchHandle, DataError, DataPlane, RagFilter};
+
+/// Lamport clock + replica id: totally orders operations across replicas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LamportTs {
+    pub counter: u64,
+    pub replica_id: u32,
+}
+
+type DepCheck = Arc<dyn Fn(&[ArrowBatchHandle]) -> bool + Send + Sync>;
+type Merge = Arc<dyn Fn(&[ArrowBatchHandle], ArrowBatchHandle) -> ArrowBatchHandle + Send + Sync>;
+
+/// A single operation in the bayou log.
+#[derive(Clone)]
+pub struct BayouOp {
+    pub ts: LamportTs,
+    pub table: String,
+    pub batch: ArrowBatchHandle,
+    /// Checked against the table's materialized state *at this point in replay*; if it returns
+    /// false, `merge` runs instead of applying `batch` directly.
+    pub dep_check: DepCheck,
+    /// Resolves a failed dependency check into the batch that actually gets applied.
+    pub merge: Merge,
+}
+
+fn default_dep_check() -> DepCheck {
+    Arc::new(|_state: &[ArrowBatchHandle]| true)
+}
+
+fn default_merge() -> Merge {
+    Arc::new(|_state: &[ArrowBatchHandle], incoming: ArrowBatchHandle| incoming)
+}
+
+struct Inner {
+    replica_id: u32,
+    clock: u64,
+    committed: Vec<BayouOp>,
+    tentative: Vec<BayouOp>,
+    log_file: Option<PathBuf>,
+}
+
+/// Bayou-style operation log `DataPlane`: durable, single-writer-per-replica, merges across
+/// replicas via [`BayouLogDataPlane::receive_remote_op`].
+///
+/// NOTE: does not implement SQL queries or RAG search; it's a durability layer for
+/// Control/Knowledge append tables (e.g. StateSnapshots/StateDeltas), not a query engine —
+/// pair it behind `MultiDataPlane` with `InMemorySpineDataPlane`/`DataFusionDataPlane` for that.
+pub struct BayouLogDataPlane {
+    inner: Mutex<Inner>,
+}
+
+impl BayouLogDataPlane {
+    /// Start a fresh, in-memory-only log (no durability).
+    pub fn new(replica_id: u32) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                replica_id,
+                clock: 0,
+                committed: Vec::new(),
+                tentative: Vec::new(),
+                log_file: None,
+            }),
+        }
+    }
+
+    /// Open (or create) a durable log backed by `path`, replaying any existing operations.
+    pub fn open(path: impl AsRef<Path>, replica_id: u32) -> Result<Self, DataError> {
+        let path = path.as_ref().to_path_buf();
+        let tentative = if path.exists() {
+            replay_log(&path)?
+        } else {
+            Vec::new()
+        };
+        let clock = tentative.iter().map(|op| op.ts.counter).max().unwrap_or(0);
+        Ok(Self {
+            inner: Mutex::new(Inner {
+                replica_id,
+                clock,
+                committed: Vec::new(),
+                tentative,
+                log_file: Some(path),
+            }),
+        })
+    }
+
+    fn next_ts(inner: &mut Inner) -> LamportTs {
+        inner.clock += 1;
+        LamportTs {
+            counter: inner.clock,
+            replica_id: inner.replica_id,
+        }
+    }
+
+    fn persist_op(inner: &Inner, op: &BayouOp) -> Result<(), DataError> {
+        let Some(path) = &inner.log_file else { return Ok(()) };
+        append_op(path, op)
+    }
+
+    /// Submit a fully-formed op (used for remote-originated writes or custom dependency
+    /// checks/merges); local `DataPlane::append_batches` calls use the trivial default op.
+    pub async fn submit_op(&self, mut op: BayouOp) -> Result<(), DataError> {
+        let mut inner = self.inner.lock().await;
+        if op.ts.counter == 0 {
+            op.ts = Self::next_ts(&mut inner);
+        } else {
+            inner.clock = inner.clock.max(op.ts.counter);
+        }
+        Self::persist_op(&inner, &op)?;
+        let idx = inner.tentative.partition_point(|existing| existing.ts < op.ts);
+        inner.tentative.insert(idx, op);
+        Ok(())
+    }
+
+    /// Merge in an operation received from another replica, which may have a timestamp earlier
+    /// than ops already applied locally: roll back tentative ops past its insertion point,
+    /// splice it in, and let replay re-run every dependency check forward from there.
+    pub async fn receive_remote_op(&self, op: BayouOp) -> Result<(), DataError> {
+        let mut inner = self.inner.lock().await;
+        inner.clock = inner.clock.max(op.ts.counter);
+        Self::persist_op(&inner, &op)?;
+        let idx = inner.tentative.partition_point(|existing| existing.ts < op.ts);
+        inner.tentative.insert(idx, op);
+        Ok(())
+    }
+
+    /// Materialize a table's current state: committed prefix, then tentative suffix replayed
+    /// with each op's dependency check re-evaluated against the state built so far.
+    fn materialize_locked(inner: &Inner, table: &str) -> Vec<ArrowBatchHandle> {
+        let mut state: Vec<ArrowBatchHandle> = Vec::new();
+        for op in inner.committed.iter().chain(inner.tentative.iter()) {
+            if op.table != table {
+                continue;
+            }
+            if (op.dep_check)(&state) {
+                state.push(Arc::clone(&op.batch));
+            } else {
+                state.push((op.merge)(&state, Arc::clone(&op.batch)));
+            }
+        }
+        state
+    }
+
+    /// Freeze the current tentative suffix into the committed prefix, consolidating each
+    /// table's replayed state into a single snapshot batch so the in-memory op list does not
+    /// grow unbounded. The on-disk log file is not rewritten; it keeps every appended op.
+    pub async fn checkpoint(&self) -> Result<(), DataError> {
+        let mut inner = self.inner.lock().await;
+        if inner.tentative.is_empty() {
+            return Ok(());
+        }
+
+        let mut tables: Vec<String> = inner
+            .tentative
+            .iter()
+            .map(|op| op.table.clone())
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        tables.sort();
+
+        let latest_ts = inner.tentative.last().map(|op| op.ts).unwrap_or(LamportTs {
+            counter: inner.clock,
+            replica_id: inner.replica_id,
+        });
+
+        let mut snapshots = Vec::new();
+        for table in &tables {
+            let state = Self::materialize_locked(&inner, table);
+            let Some(first) = state.first() else { continue };
+            let schema = first.schema();
+            let snapshot = concat_batches(&schema, state.iter().map(|b| b.as_ref()))
+                .map_err(|e| DataError {
+                    message: format!("checkpoint concat error for table '{table}': {e}"),
+                })?;
+            snapshots.push(BayouOp {
+                ts: latest_ts,
+                table: table.clone(),
+                batch: Arc::new(snapshot),
+                dep_check: default_dep_check(),
+                merge: default_merge(),
+            });
+        }
+
+        inner.committed.retain(|op| !tables.contains(&op.table));
+        inner.committed.extend(snapshots);
+        inner.tentative.clear();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DataPlane for BayouLogDataPlane {
+    async fn query(&self, _sql: &str) -> Result<Vec<ArrowBatchHandle>, DataError> {
+        Err(DataError {
+            message: "BayouLogDataPlane does not support SQL queries".into(),
+        })
+    }
+
+    async fn append_batches(
+        &self,
+        table: &str,
+        batches: Vec<ArrowBatchHandle>,
+    ) -> Result<(), DataError> {
+        for batch in batches {
+            self.submit_op(BayouOp {
+                ts: LamportTs { counter: 0, replica_id: 0 },
+                table: table.to_string(),
+                batch,
+                dep_check: default_dep_check(),
+                merge: default_merge(),
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn rag_candidates(
+        &self,
+        _collection: &str,
+        _query_embedding: &[f32],
+        _top_k: usize,
+        _filter: Option<&RagFilter>,
+    ) -> Result<ArrowBatchHandle, DataError> {
+        Err(DataError {
+            message: "BayouLogDataPlane has no RAG".into(),
+        })
+    }
+}
+
+impl BayouLogDataPlane {
+    /// Best-effort debug hook mirroring `InMemorySpineDataPlane::get_table`.
+    pub async fn get_table(&self, table: &str) -> Vec<ArrowBatchHandle> {
+        let inner = self.inner.lock().await;
+        Self::materialize_locked(&inner, table)
+    }
+}
+
+// --- on-disk framing: [u32 table_len][table bytes][u64 counter][u32 replica_id][u64 ipc_len][arrow IPC stream bytes] ---
+
+fn append_op(path: &Path, op: &BayouOp) -> Result<(), DataError> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| DataError {
+            message: format!("bayou log io error: {e}"),
+        })?;
+
+    let table_bytes = op.table.as_bytes();
+    file.write_all(&(table_bytes.len() as u32).to_le_bytes())
+        .and_then(|_| file.write_all(table_bytes))
+        .and_then(|_| file.write_all(&op.ts.counter.to_le_bytes()))
+        .and_then(|_| file.write_all(&op.ts.replica_id.to_le_bytes()))
+        .map_err(|e| DataError {
+            message: format!("bayou log io error: {e}"),
+        })?;
+
+    let mut ipc_bytes = Vec::new();
+    {
+        let mut writer = StreamWriter::try_new(&mut ipc_bytes, &op.batch.schema())
+            .map_err(|e| DataError { message: format!("bayou log ipc error: {e}") })?;
+        writer.write(&op.batch).map_err(|e| DataError { message: format!("bayou log ipc error: {e}") })?;
+        writer.finish().map_err(|e| DataError { message: format!("bayou log ipc error: {e}") })?;
+    }
+    file.write_all(&(ipc_bytes.len() as u64).to_le_bytes())
+        .and_then(|_| file.write_all(&ipc_bytes))
+        .map_err(|e| DataError {
+            message: format!("bayou log io error: {e}"),
+        })?;
+
+    Ok(())
+}
+
+fn replay_log(path: &Path) -> Result<Vec<BayouOp>, DataError> {
+    let mut file = File::open(path).map_err(|e| DataError {
+        message: format!("bayou log io error: {e}"),
+    })?;
+    let mut ops = Vec::new();
+
+    loop {
+        let mut len_buf = [0u8; 4];
+        match file.read_exact(&mut len_buf) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(DataError { message: format!("bayou log io error: {e}") }),
+        }
+        let table_len = u32::from_le_bytes(len_buf) as usize;
+        let mut table_buf = vec![0u8; table_len];
+        file.read_exact(&mut table_buf).map_err(|e| DataError { message: format!("bayou log io error: {e}") })?;
+        let table = String::from_utf8(table_buf).map_err(|e| DataError { message: format!("bayou log parse error: {e}") })?;
+
+        let mut counter_buf = [0u8; 8];
+        file.read_exact(&mut counter_buf).map_err(|e| DataError { message: format!("bayou log io error: {e}") })?;
+        let counter = u64::from_le_bytes(counter_buf);
+
+        let mut replica_buf = [0u8; 4];
+        file.read_exact(&mut replica_buf).map_err(|e| DataError { message: format!("bayou log io error: {e}") })?;
+        let replica_id = u32::from_le_bytes(replica_buf);
+
+        let mut ipc_len_buf = [0u8; 8];
+        file.read_exact(&mut ipc_len_buf).map_err(|e| DataError { message: format!("bayou log io error: {e}") })?;
+        let ipc_len = u64::from_le_bytes(ipc_len_buf) as usize;
+        let mut ipc_buf = vec![0u8; ipc_len];
+        file.read_exact(&mut ipc_buf).map_err(|e| DataError { message: format!("bayou log io error: {e}") })?;
+
+        let mut reader = StreamReader::try_new(ipc_buf.as_slice(), None)
+            .map_err(|e| DataError { message: format!("bayou log ipc error: {e}") })?;
+        let batch = reader
+            .next()
+            .ok_or_else(|| DataError { message: "bayou log ipc error: empty batch".into() })?
+            .map_err(|e| DataError { message: format!("bayou log ipc error: {e}") })?;
+
+        ops.push(BayouOp {
+            ts: LamportTs { counter, replica_id },
+            table,
+            batch: Arc::new(batch),
+            dep_check: default_dep_check(),
+            merge: default_merge(),
+        });
+    }
+
+    ops.sort_by_key(|op| op.ts);
+    Ok(ops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::StringArray;
+    use arrow::datatypes::Schema;
+
+    fn row(id: &str, value: &str) -> ArrowBatchHandle {
+        let schema = Arc::new(Schema::new(vec![
+            arrow::datatypes::Field::new("id", arrow::datatypes::DataType::Utf8, false),
+            arrow::datatypes::Field::new("value", arrow::datatypes::DataType::Utf8, false),
+        ]));
+        Arc::new(
+            arrow::record_batch::RecordBatch::try_new(
+                schema,
+                vec![
+                    Arc::new(StringArray::from(vec![id])),
+                    Arc::new(StringArray::from(vec![value])),
+                ],
+            )
+            .unwrap(),
+        )
+    }
+
+    fn value_of(batch: &ArrowBatchHandle) -> String {
+        batch
+            .column_by_name("value")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap()
+            .value(0)
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn append_batches_then_get_table_replays_in_local_order() {
+        let plane = BayouLogDataPlane::new(1);
+        plane.append_batches("Notes", vec![row("a", "first")]).await.unwrap();
+        plane.append_batches("Notes", vec![row("b", "second")]).await.unwrap();
+
+        let state = plane.get_table("Notes").await;
+        assert_eq!(state.iter().map(value_of).collect::<Vec<_>>(), vec!["first", "second"]);
+        assert!(plane.get_table("OtherTable").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn receive_remote_op_splices_by_timestamp_ahead_of_later_local_ops() {
+        let plane = BayouLogDataPlane::new(1);
+        plane.append_batches("Notes", vec![row("local-1", "local")]).await.unwrap();
+
+        // A remote op stamped earlier than the local op above should be replayed first.
+        plane
+            .receive_remote_op(BayouOp {
+                ts: LamportTs { counter: 0, replica_id: 2 },
+                table: "Notes".to_string(),
+                batch: row("remote-1", "remote"),
+                dep_check: default_dep_check(),
+                merge: default_merge(),
+            })
+            .await
+            .unwrap();
+
+        let state = plane.get_table("Notes").await;
+        assert_eq!(state.iter().map(value_of).collect::<Vec<_>>(), vec!["remote", "local"]);
+    }
+
+    #[tokio::test]
+    async fn failed_dependency_check_runs_merge_instead_of_applying_the_batch_directly() {
+        let plane = BayouLogDataPlane::new(1);
+        plane.append_batches("Notes", vec![row("a", "first")]).await.unwrap();
+
+        plane
+            .submit_op(BayouOp {
+                ts: LamportTs { counter: 0, replica_id: 0 },
+                table: "Notes".to_string(),
+                batch: row("b", "second"),
+                dep_check: Arc::new(|state: &[ArrowBatchHandle]| state.is_empty()),
+                merge: Arc::new(|_state, _incoming| row("b", "merged")),
+            })
+            .await
+            .unwrap();
+
+        let state = plane.get_table("Notes").await;
+        assert_eq!(state.iter().map(value_of).collect::<Vec<_>>(), vec!["first", "merged"]);
+    }
+
+    #[tokio::test]
+    async fn checkpoint_consolidates_the_tentative_suffix_into_one_committed_snapshot() {
+        let plane = BayouLogDataPlane::new(1);
+        plane.append_batches("Notes", vec![row("a", "first")]).await.unwrap();
+        plane.append_batches("Notes", vec![row("b", "second")]).await.unwrap();
+
+        plane.checkpoint().await.unwrap();
+
+        let inner = plane.inner.lock().await;
+        assert_eq!(inner.tentative.len(), 0);
+        assert_eq!(inner.committed.len(), 1);
+        drop(inner);
+
+        // Materialized contents are unchanged by the checkpoint, just consolidated into fewer ops.
+        let state = plane.get_table("Notes").await;
+        assert_eq!(state.len(), 1);
+        assert_eq!(state[0].num_rows(), 2);
+    }
+
+    #[tokio::test]
+    async fn open_replays_a_previously_persisted_log_from_disk() {
+        let path = "target/test_bayou_log_reopen.bin";
+        let _ = std::fs::remove_file(path);
+
+        {
+            let plane = BayouLogDataPlane::open(path, 1).unwrap();
+            plane.append_batches("Notes", vec![row("a", "first")]).await.unwrap();
+            plane.append_batches("Notes", vec![row("b", "second")]).await.unwrap();
+        }
+
+        let reopened = BayouLogDataPlane::open(path, 1).unwrap();
+        let state = reopened.get_table("Notes").await;
+        assert_eq!(state.iter().map(value_of).collect::<Vec<_>>(), vec!["first", "second"]);
+    }
+}
@@ -0,0 +1,213 @@
+//! DataFusion-backed Spine `DataPlane` that registers the full `define_tables!` schema set up
+//! front and validates every appended batch against it.
+
+use std::{collections::HashMap, sync::Arc};
+
+use arrow::datatypes::Schema;
+use async_trait::async_trait;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::SessionContext;
+use goni_schema::plane::Plane;
+use tokio::sync::Mutex;
+
+use crate::{ArrowBatch, ArrowBatchHandle, DataError, DataPlane, RagFilter};
+
+struct Table {
+    plane: Plane,
+    schema: Arc<Schema>,
+    batches: Vec<ArrowBatchHandle>,
+}
+
+/// DataFusion-backed Spine: registers every `define_tables!` table's schema at construction (so
+/// `query` can `SELECT` from a table before anything has been appended to it), then validates
+/// each appended batch's schema against the registered one and re-runs
+/// `goni_schema::macros::__check_txt_invariants` before committing — the same TXT-axiom check
+/// `$Table::new` enforces at construction time, now also enforced at ingest time so a
+/// LargeUtf8 column can't slip into a Control/Execution table through `append_batches` instead.
+///
+/// This plays the "spine" role in `MultiDataPlane` (general-purpose Arrow tables); it has no
+/// RAG/ANN index of its own, so `rag_candidates` reports unsupported the way `NullDataPlane`'s
+/// does, and `keyword_candidates` falls back to `DataPlane`'s default "unsupported" impl.
+pub struct DataFusionDataPlane {
+    tables: Mutex<HashMap<String, Table>>,
+}
+
+impl DataFusionDataPlane {
+    /// Builds a plane with every `goni_schema::table_registry()` table pre-registered (empty).
+    pub fn new() -> Self {
+        let tables = goni_schema::table_registry()
+            .into_iter()
+            .map(|(name, plane, schema)| {
+                (
+                    name.to_string(),
+                    Table {
+                        plane,
+                        schema: Arc::new(schema),
+                        batches: Vec::new(),
+                    },
+                )
+            })
+            .collect();
+        Self {
+            tables: Mutex::new(tables),
+        }
+    }
+}
+
+impl Default for DataFusionDataPlane {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl DataPlane for DataFusionDataPlane {
+    /// Registers every known table (even empty ones, as a zero-row `MemTable`) and runs `sql`
+    /// through a fresh DataFusion `SessionContext`.
+    async fn query(&self, sql: &str) -> Result<Vec<ArrowBatchHandle>, DataError> {
+        let tables = self.tables.lock().await;
+
+        let ctx = SessionContext::new();
+        for (name, table) in tables.iter() {
+            let partition: Vec<ArrowBatch> = if table.batches.is_empty() {
+                vec![ArrowBatch::new_empty(table.schema.clone())]
+            } else {
+                table.batches.iter().map(|b| (**b).clone()).collect()
+            };
+            let mem_table = MemTable::try_new(table.schema.clone(), vec![partition]).map_err(|e| DataError {
+                message: format!("failed to register table '{name}': {e}"),
+            })?;
+            ctx.register_table(name.as_str(), Arc::new(mem_table))
+                .map_err(|e| DataError {
+                    message: format!("failed to register table '{name}': {e}"),
+                })?;
+        }
+        drop(tables);
+
+        let df = ctx.sql(sql).await.map_err(|e| DataError {
+            message: format!("sql planning error: {e}"),
+        })?;
+        let results = df.collect().await.map_err(|e| DataError {
+            message: format!("sql execution error: {e}"),
+        })?;
+
+        Ok(results.into_iter().map(Arc::new).collect())
+    }
+
+    /// Validates each batch's schema against the registered table's schema, re-runs
+    /// `__check_txt_invariants`, and only then appends — so ingest can't introduce a column
+    /// that the table wasn't declared with, or a LargeUtf8 column in a Control/Execution table.
+    async fn append_batches(
+        &self,
+        table: &str,
+        batches: Vec<ArrowBatchHandle>,
+    ) -> Result<(), DataError> {
+        let mut tables = self.tables.lock().await;
+        let entry = tables.get_mut(table).ok_or_else(|| DataError {
+            message: format!("unknown table '{table}': not in goni_schema::table_registry()"),
+        })?;
+
+        goni_schema::macros::__check_txt_invariants(table, &entry.plane, entry.schema.as_ref())
+            .map_err(|e| DataError { message: e.to_string() })?;
+
+        for batch in &batches {
+            if batch.schema().as_ref() != entry.schema.as_ref() {
+                return Err(DataError {
+                    message: format!(
+                        "schema mismatch appending to '{table}': expected {:?}, got {:?}",
+                        entry.schema, batch.schema()
+                    ),
+                });
+            }
+        }
+
+        entry.batches.extend(batches);
+        Ok(())
+    }
+
+    async fn rag_candidates(
+        &self,
+        _collection: &str,
+        _query_embedding: &[f32],
+        _top_k: usize,
+        _filter: Option<&RagFilter>,
+    ) -> Result<ArrowBatchHandle, DataError> {
+        Err(DataError {
+            message: "DataFusionDataPlane has no RAG index; pair it with a RAG backend via MultiDataPlane".into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::StringArray;
+    use arrow::datatypes::{DataType, Field};
+
+    fn chunks_schema() -> Arc<Schema> {
+        goni_schema::table_registry()
+            .into_iter()
+            .find(|(name, _, _)| *name == "Chunks")
+            .map(|(_, _, schema)| Arc::new(schema))
+            .expect("goni_schema::table_registry() should register a Chunks table")
+    }
+
+    #[tokio::test]
+    async fn query_selects_from_every_registered_table_even_when_empty() {
+        let plane = DataFusionDataPlane::new();
+
+        // Every `define_tables!` table is pre-registered as a zero-row `MemTable`, so a `SELECT
+        // COUNT(*)` against one should succeed (not "table not found") before anything is appended.
+        let results = plane.query("SELECT COUNT(*) FROM Chunks").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].num_rows(), 1);
+    }
+
+    #[tokio::test]
+    async fn append_batches_rejects_unknown_table() {
+        let plane = DataFusionDataPlane::new();
+        let empty: ArrowBatchHandle = Arc::new(ArrowBatch::new_empty(chunks_schema()));
+
+        let err = plane
+            .append_batches("NotARealTable", vec![empty])
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("unknown table"));
+    }
+
+    #[tokio::test]
+    async fn append_batches_rejects_schema_mismatch() {
+        let plane = DataFusionDataPlane::new();
+
+        let bad_schema = Arc::new(Schema::new(vec![Field::new("nope", DataType::Utf8, false)]));
+        let bad_batch: ArrowBatchHandle = Arc::new(
+            ArrowBatch::try_new(bad_schema, vec![Arc::new(StringArray::from(vec!["x"]))]).unwrap(),
+        );
+
+        let err = plane
+            .append_batches("Chunks", vec![bad_batch])
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("schema mismatch"));
+    }
+
+    #[tokio::test]
+    async fn append_batches_accepts_a_batch_matching_the_registered_schema() {
+        let plane = DataFusionDataPlane::new();
+        let matching: ArrowBatchHandle = Arc::new(ArrowBatch::new_empty(chunks_schema()));
+
+        plane.append_batches("Chunks", vec![matching]).await.unwrap();
+
+        // Round-trips through DataFusion without the earlier "unknown table"/"schema mismatch"
+        // errors, confirming the appended batch was actually registered under "Chunks".
+        let results = plane.query("SELECT COUNT(*) FROM Chunks").await.unwrap();
+        assert_eq!(results[0].num_rows(), 1);
+    }
+
+    #[tokio::test]
+    async fn rag_candidates_reports_unsupported() {
+        let plane = DataFusionDataPlane::new();
+        let err = plane.rag_candidates("Chunks", &[0.0], 10, None).await.unwrap_err();
+        assert!(err.message.contains("no RAG index"));
+    }
+}
@@ -10,9 +10,15 @@ pub use qdrant::QdrantDataPlane;
 pub mod spine_mem;
 pub use spine_mem::InMemorySpineDataPlane;
 
+pub mod bayou;
+pub use bayou::BayouLogDataPlane;
+
 pub mod multi;
 pub use multi::MultiDataPlane;
 
+pub mod datafusion_plane;
+pub use datafusion_plane::DataFusionDataPlane;
+
 pub type ArrowBatch = RecordBatch;
 pub type ArrowBatchHandle = Arc<ArrowBatch>;
 
@@ -22,6 +28,47 @@ pub struct DataError {
     pub message: String,
 }
 
+/// Numeric payload key whose value must fall within `[min, max]`; either bound may be omitted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RagFilterRange {
+    pub key: String,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// Structured scope for [`DataPlane::rag_candidates`], narrowing a collection to a subset of
+/// rows before vector scoring runs. Every clause is AND-ed together; an empty filter matches
+/// everything.
+///
+/// This is essential once multiple projects/tenants share one collection: a `TaskClass` or
+/// per-request scope (see `GoniKernel::solve_prompt`) can restrict candidates to e.g. one
+/// tenant, one language, or one doc-age window, instead of searching the whole collection.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RagFilter {
+    /// Exact match on a payload key, e.g. `("language", "rust")` or `("tenant", "acme")`.
+    pub equals: Vec<(String, String)>,
+    /// Payload key whose string value must start with this prefix, e.g. `source_path`.
+    pub path_prefix: Option<(String, String)>,
+    /// Payload key (array-valued) that must contain at least one of these tags.
+    pub any_tag: Option<(String, Vec<String>)>,
+    /// Payload key whose numeric value must fall within a range (recency windows, etc.).
+    pub range: Option<RagFilterRange>,
+}
+
+impl RagFilter {
+    pub fn is_empty(&self) -> bool {
+        self.equals.is_empty() && self.path_prefix.is_none() && self.any_tag.is_none() && self.range.is_none()
+    }
+
+    /// Convenience constructor for the common case: scope to a single payload key/value.
+    pub fn equals(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            equals: vec![(key.into(), value.into())],
+            ..Self::default()
+        }
+    }
+}
+
 /// The Arrow Spine: all structured data flows through this trait.
 #[async_trait]
 pub trait DataPlane: Send + Sync {
@@ -38,16 +85,57 @@ pub trait DataPlane: Send + Sync {
         batches: Vec<ArrowBatchHandle>,
     ) -> Result<(), DataError>;
 
-    /// RAG/ANN query: return top-k candidate chunks with embeddings.
+    /// RAG/ANN query: return top-k candidate chunks with embeddings, optionally scoped by
+    /// `filter` (see [`RagFilter`]). Backends that cannot evaluate a filter should fail closed
+    /// rather than silently searching the whole collection.
     async fn rag_candidates(
         &self,
         collection: &str,
         query_embedding: &[f32],
         top_k: usize,
+        filter: Option<&RagFilter>,
     ) -> Result<ArrowBatchHandle, DataError>;
+
+    /// Lexical/BM25 companion to `rag_candidates`, in the same `id/text/tokens/embedding` schema,
+    /// used for hybrid (RRF) retrieval so exact-term queries an embedding misses still surface.
+    ///
+    /// This is optional: backends without a lexical index keep the default, which reports
+    /// "unsupported" so callers (see `GoniKernel::solve_prompt`) gracefully degrade to pure
+    /// vector search instead of erroring out.
+    async fn keyword_candidates(
+        &self,
+        _collection: &str,
+        _query_text: &str,
+        _top_k: usize,
+        _filter: Option<&RagFilter>,
+    ) -> Result<ArrowBatchHandle, DataError> {
+        Err(DataError {
+            message: "keyword_candidates not supported by this DataPlane".into(),
+        })
+    }
+
+    /// Chunk a raw document with `chunker` and append the resulting rows (carrying
+    /// `source_path`/`start_byte`/`end_byte` provenance) into `table`, so `rag_candidates` can
+    /// later surface that provenance for `solve_prompt`'s `path:start-end` citations.
+    ///
+    /// This is optional: backends without an ingestion path keep the default, which reports
+    /// "unsupported".
+    async fn ingest_document(
+        &self,
+        _table: &str,
+        _source_path: &str,
+        _language: goni_chunker::Language,
+        _text: &str,
+        _chunker: &dyn goni_chunker::Chunker,
+    ) -> Result<(), DataError> {
+        Err(DataError {
+            message: "ingest_document not supported by this DataPlane".into(),
+        })
+    }
 }
 
-/// Stub implementation for now – replace with DuckDB + LanceDB.
+/// No-op stub: every call succeeds (or reports unsupported) without storing or returning
+/// anything. See [`DataFusionDataPlane`] for the real Spine implementation.
 pub struct NullDataPlane;
 
 #[async_trait]
@@ -72,6 +160,7 @@ impl DataPlane for NullDataPlane {
         _collection: &str,
         _query_embedding: &[f32],
         _top_k: usize,
+        _filter: Option<&RagFilter>,
     ) -> Result<ArrowBatchHandle, DataError> {
         Err(DataError {
             message: "NullDataPlane has no RAG".into(),
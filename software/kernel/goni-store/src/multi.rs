@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 
-use crate::{ArrowBatchHandle, DataError, DataPlane};
+use crate::{ArrowBatchHandle, DataError, DataPlane, RagFilter};
 
 /// A small router that lets us combine:
 /// - a general-purpose "spine" DataPlane (append-only tables), and
@@ -53,7 +53,165 @@ impl DataPlane for MultiDataPlane {
         collection: &str,
         query_embedding: &[f32],
         top_k: usize,
+        filter: Option<&RagFilter>,
     ) -> Result<ArrowBatchHandle, DataError> {
-        self.rag.rag_candidates(collection, query_embedding, top_k).await
+        self.rag.rag_candidates(collection, query_embedding, top_k, filter).await
+    }
+
+    /// Chunk text (for BM25-lite keyword search) lives in the Arrow spine tables, not the ANN
+    /// backend, so this routes to `spine` rather than `rag`.
+    async fn keyword_candidates(
+        &self,
+        collection: &str,
+        query_text: &str,
+        top_k: usize,
+        filter: Option<&RagFilter>,
+    ) -> Result<ArrowBatchHandle, DataError> {
+        self.spine.keyword_candidates(collection, query_text, top_k, filter).await
+    }
+
+    /// Chunked documents are RAG content, so this routes to `rag` (e.g. Qdrant) rather than
+    /// `spine`, matching `rag_candidates`'s routing.
+    async fn ingest_document(
+        &self,
+        table: &str,
+        source_path: &str,
+        language: goni_chunker::Language,
+        text: &str,
+        chunker: &dyn goni_chunker::Chunker,
+    ) -> Result<(), DataError> {
+        self.rag.ingest_document(table, source_path, language, text, chunker).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{StringArray, UInt32Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts calls per method instead of storing/serving real data, so tests can assert which
+    /// backing DataPlane a `MultiDataPlane` call was routed to.
+    #[derive(Default)]
+    struct RecordingDataPlane {
+        query_calls: AtomicUsize,
+        append_calls: AtomicUsize,
+        rag_calls: AtomicUsize,
+        keyword_calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl DataPlane for RecordingDataPlane {
+        async fn query(&self, _sql: &str) -> Result<Vec<ArrowBatchHandle>, DataError> {
+            self.query_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Vec::new())
+        }
+
+        async fn append_batches(&self, _table: &str, _batches: Vec<ArrowBatchHandle>) -> Result<(), DataError> {
+            self.append_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn rag_candidates(
+            &self,
+            _collection: &str,
+            _query_embedding: &[f32],
+            _top_k: usize,
+            _filter: Option<&RagFilter>,
+        ) -> Result<ArrowBatchHandle, DataError> {
+            self.rag_calls.fetch_add(1, Ordering::SeqCst);
+            Err(DataError { message: "RecordingDataPlane has no RAG".into() })
+        }
+
+        async fn keyword_candidates(
+            &self,
+            _collection: &str,
+            _query_text: &str,
+            _top_k: usize,
+            _filter: Option<&RagFilter>,
+        ) -> Result<ArrowBatchHandle, DataError> {
+            self.keyword_calls.fetch_add(1, Ordering::SeqCst);
+            Err(DataError { message: "RecordingDataPlane has no lexical index".into() })
+        }
+    }
+
+    /// Schema `looks_like_rag_ingest` matches: plain utf8 `id`/`text` and u32 `tokens`, no `row_id`.
+    fn rag_shaped_batch() -> ArrowBatchHandle {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("text", DataType::Utf8, false),
+            Field::new("tokens", DataType::UInt32, false),
+        ]));
+        Arc::new(
+            arrow::record_batch::RecordBatch::try_new(
+                schema,
+                vec![
+                    Arc::new(StringArray::from(vec!["a"])),
+                    Arc::new(StringArray::from(vec!["hello"])),
+                    Arc::new(UInt32Array::from(vec![1u32])),
+                ],
+            )
+            .unwrap(),
+        )
+    }
+
+    /// A spine-table-shaped batch: carries `row_id`, which `looks_like_rag_ingest` excludes.
+    fn spine_shaped_batch() -> ArrowBatchHandle {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("row_id", DataType::Utf8, false),
+            Field::new("value", DataType::Utf8, false),
+        ]));
+        Arc::new(
+            arrow::record_batch::RecordBatch::try_new(
+                schema,
+                vec![
+                    Arc::new(StringArray::from(vec!["1"])),
+                    Arc::new(StringArray::from(vec!["v"])),
+                ],
+            )
+            .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn append_batches_routes_rag_shaped_batches_to_rag() {
+        let spine = Arc::new(RecordingDataPlane::default());
+        let rag = Arc::new(RecordingDataPlane::default());
+        let multi = MultiDataPlane::new(spine.clone(), rag.clone());
+
+        multi.append_batches("Chunks", vec![rag_shaped_batch()]).await.unwrap();
+
+        assert_eq!(rag.append_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(spine.append_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn append_batches_routes_other_batches_to_spine() {
+        let spine = Arc::new(RecordingDataPlane::default());
+        let rag = Arc::new(RecordingDataPlane::default());
+        let multi = MultiDataPlane::new(spine.clone(), rag.clone());
+
+        multi.append_batches("Requests", vec![spine_shaped_batch()]).await.unwrap();
+
+        assert_eq!(spine.append_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(rag.append_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn rag_candidates_and_keyword_candidates_route_to_their_dedicated_backend() {
+        let spine = Arc::new(RecordingDataPlane::default());
+        let rag = Arc::new(RecordingDataPlane::default());
+        let multi = MultiDataPlane::new(spine.clone(), rag.clone());
+
+        let _ = multi.rag_candidates("Chunks", &[0.0], 10, None).await;
+        let _ = multi.keyword_candidates("Chunks", "query", 10, None).await;
+        let _ = multi.query("select 1").await;
+
+        assert_eq!(rag.rag_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(spine.rag_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(spine.keyword_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(rag.keyword_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(spine.query_calls.load(Ordering::SeqCst), 1);
     }
 }
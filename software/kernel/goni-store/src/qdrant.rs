@@ -3,34 +3,80 @@ use std::sync::Arc;
 use arrow_array::{builder::StringBuilder, types::UInt32Type, Array, ArrayRef, FixedSizeListArray, Float32Array, StringArray, UInt32Array};
 use arrow_schema::{DataType, Field, Schema};
 use async_trait::async_trait;
+use goni_embed::Embedder;
 use serde::{Deserialize, Serialize};
-use goni_embed::embed;
 
-use crate::{ArrowBatch, ArrowBatchHandle, DataError, DataPlane};
+use crate::{ArrowBatch, ArrowBatchHandle, DataError, DataPlane, RagFilter};
 
 /// Qdrant-backed DataPlane for RAG queries and ingestion.
 pub struct QdrantDataPlane {
     client: reqwest::Client,
     base_url: String,
-    embed_dim: usize,
+    embedder: Arc<dyn Embedder>,
 }
 
 impl QdrantDataPlane {
-    pub fn new(base_url: impl Into<String>) -> Self {
-        let embed_dim = std::env::var("EMBED_DIM")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(1024);
+    /// `embedder` must be the same instance used for queries, so ingestion and query vectors
+    /// always share one dimension and one semantic space.
+    pub fn new(base_url: impl Into<String>, embedder: Arc<dyn Embedder>) -> Self {
         Self {
             client: reqwest::Client::new(),
             base_url: base_url.into(),
-            embed_dim,
+            embedder,
         }
     }
+}
+
+/// Translate a [`RagFilter`] into a Qdrant `filter` clause: every configured clause becomes one
+/// entry in `must`, so all clauses are AND-ed together. Returns `None` for an empty filter so the
+/// request omits the `filter` field entirely (matches the whole collection, as before).
+///
+/// `path_prefix` has no native "starts with" operator in Qdrant's filter DSL, so it is
+/// approximated with a full-text `match.text` clause — good enough to scope to a directory, but
+/// callers relying on an exact prefix boundary should still post-filter.
+fn build_filter(filter: &RagFilter) -> Option<serde_json::Value> {
+    if filter.is_empty() {
+        return None;
+    }
+
+    let mut must = Vec::new();
+
+    for (key, value) in &filter.equals {
+        must.push(serde_json::json!({
+            "key": key,
+            "match": { "value": value },
+        }));
+    }
 
-    fn embed(&self, text: &str) -> Vec<f32> {
-        embed(text, self.embed_dim)
+    if let Some((key, prefix)) = &filter.path_prefix {
+        must.push(serde_json::json!({
+            "key": key,
+            "match": { "text": prefix },
+        }));
+    }
+
+    if let Some((key, tags)) = &filter.any_tag {
+        must.push(serde_json::json!({
+            "key": key,
+            "match": { "any": tags },
+        }));
+    }
+
+    if let Some(range) = &filter.range {
+        let mut range_clause = serde_json::Map::new();
+        if let Some(min) = range.min {
+            range_clause.insert("gte".into(), serde_json::json!(min));
+        }
+        if let Some(max) = range.max {
+            range_clause.insert("lte".into(), serde_json::json!(max));
+        }
+        must.push(serde_json::json!({
+            "key": range.key,
+            "range": range_clause,
+        }));
     }
+
+    Some(serde_json::json!({ "must": must }))
 }
 
 #[derive(Serialize)]
@@ -39,6 +85,8 @@ struct SearchRequest<'a> {
     limit: usize,
     with_vector: bool,
     with_payload: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter: Option<serde_json::Value>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -116,30 +164,73 @@ impl DataPlane for QdrantDataPlane {
                     message: "tokens column not u32".into(),
                 })?;
 
-            let mut points = Vec::with_capacity(batch.num_rows());
+            // Provenance columns are optional: only present for rows produced by
+            // `goni-chunker` (see `ingest_document`).
+            let source_paths = batch
+                .schema()
+                .index_of("source_path")
+                .ok()
+                .and_then(|idx| batch.column(idx).as_any().downcast_ref::<StringArray>().cloned());
+            let start_bytes = batch
+                .schema()
+                .index_of("start_byte")
+                .ok()
+                .and_then(|idx| batch.column(idx).as_any().downcast_ref::<UInt32Array>().cloned());
+            let end_bytes = batch
+                .schema()
+                .index_of("end_byte")
+                .ok()
+                .and_then(|idx| batch.column(idx).as_any().downcast_ref::<UInt32Array>().cloned());
+
+            let mut rows = Vec::with_capacity(batch.num_rows());
             for row in 0..batch.num_rows() {
                 if ids.is_null(row) || texts.is_null(row) {
                     continue;
                 }
-                let id = ids.value(row);
-                let text = texts.value(row);
-                let tokens = tokens_arr.value(row);
-                let vector = self.embed(text);
-                let payload = serde_json::json!({
-                    "text": text,
-                    "tokens": tokens,
-                });
-                points.push(UpsertPoint {
-                    id,
-                    vector,
-                    payload,
-                });
+                let source_path = source_paths
+                    .as_ref()
+                    .filter(|arr| !arr.is_null(row))
+                    .map(|arr| arr.value(row).to_string());
+                let start_byte = start_bytes.as_ref().filter(|arr| !arr.is_null(row)).map(|arr| arr.value(row));
+                let end_byte = end_bytes.as_ref().filter(|arr| !arr.is_null(row)).map(|arr| arr.value(row));
+                rows.push((ids.value(row), texts.value(row), tokens_arr.value(row), source_path, start_byte, end_byte));
             }
 
-            if points.is_empty() {
+            if rows.is_empty() {
                 continue;
             }
 
+            // One batched embed call instead of one HTTP round-trip per row.
+            let texts_to_embed: Vec<&str> = rows.iter().map(|(_, text, ..)| *text).collect();
+            let vectors = self
+                .embedder
+                .embed_batch(&texts_to_embed)
+                .await
+                .map_err(|e| DataError {
+                    message: format!("embed error: {e}"),
+                })?;
+
+            let points: Vec<UpsertPoint> = rows
+                .into_iter()
+                .zip(vectors)
+                .map(|((id, text, tokens, source_path, start_byte, end_byte), vector)| {
+                    let mut payload = serde_json::json!({
+                        "text": text,
+                        "tokens": tokens,
+                    });
+                    if let Some(path) = source_path {
+                        payload["source_path"] = serde_json::Value::String(path);
+                    }
+                    if let Some(start) = start_byte {
+                        payload["start_byte"] = serde_json::Value::from(start);
+                    }
+                    if let Some(end) = end_byte {
+                        payload["end_byte"] = serde_json::Value::from(end);
+                    }
+                    UpsertPoint { id, vector, payload }
+                })
+                .collect();
+
             let url = format!("{}/collections/{}/points?wait=true", self.base_url, table);
             let body = UpsertRequest { points };
             let resp = self
@@ -165,6 +256,7 @@ impl DataPlane for QdrantDataPlane {
         collection: &str,
         query_embedding: &[f32],
         top_k: usize,
+        filter: Option<&RagFilter>,
     ) -> Result<ArrowBatchHandle, DataError> {
         let url = format!("{}/collections/{}/points/search", self.base_url, collection);
         let body = SearchRequest {
@@ -172,6 +264,7 @@ impl DataPlane for QdrantDataPlane {
             limit: top_k,
             with_vector: true,
             with_payload: true,
+            filter: filter.and_then(build_filter),
         };
 
         let resp = self
@@ -199,6 +292,9 @@ impl DataPlane for QdrantDataPlane {
         let mut text_builder = StringBuilder::new();
         let mut tokens: Vec<u32> = Vec::with_capacity(parsed.result.len());
         let mut embedding_vals: Vec<f32> = Vec::new();
+        let mut source_path_builder = StringBuilder::new();
+        let mut start_bytes: Vec<Option<u32>> = Vec::with_capacity(parsed.result.len());
+        let mut end_bytes: Vec<Option<u32>> = Vec::with_capacity(parsed.result.len());
 
         let mut dim: Option<usize> = None;
         for item in &parsed.result {
@@ -223,6 +319,13 @@ impl DataPlane for QdrantDataPlane {
                 .unwrap_or(0) as u32;
             tokens.push(tok_val);
 
+            match item.payload.get("source_path").and_then(|v| v.as_str()) {
+                Some(path) => source_path_builder.append_value(path),
+                None => source_path_builder.append_null(),
+            }
+            start_bytes.push(item.payload.get("start_byte").and_then(|v| v.as_u64()).map(|v| v as u32));
+            end_bytes.push(item.payload.get("end_byte").and_then(|v| v.as_u64()).map(|v| v as u32));
+
             if let Some(d) = dim {
                 if item.vector.len() != d {
                     return Err(DataError {
@@ -267,6 +370,9 @@ impl DataPlane for QdrantDataPlane {
                 DataType::FixedSizeList(item_field.clone(), dim as i32),
                 false,
             ),
+            Field::new("source_path", DataType::Utf8, true),
+            Field::new("start_byte", DataType::UInt32, true),
+            Field::new("end_byte", DataType::UInt32, true),
         ]));
 
         let columns: Vec<ArrayRef> = vec![
@@ -274,6 +380,9 @@ impl DataPlane for QdrantDataPlane {
             Arc::new(text_array),
             Arc::new(token_array),
             Arc::new(embedding_array),
+            Arc::new(source_path_builder.finish()),
+            Arc::new(UInt32Array::from(start_bytes)),
+            Arc::new(UInt32Array::from(end_bytes)),
         ];
 
         let batch = ArrowBatch::try_new(schema, columns).map_err(|e| DataError {
@@ -282,4 +391,113 @@ impl DataPlane for QdrantDataPlane {
 
         Ok(Arc::new(batch))
     }
+
+    /// Chunk a raw document with `chunker` and append the resulting rows (carrying
+    /// `source_path`/`start_byte`/`end_byte` provenance) into `table`.
+    async fn ingest_document(
+        &self,
+        table: &str,
+        source_path: &str,
+        language: goni_chunker::Language,
+        text: &str,
+        chunker: &dyn goni_chunker::Chunker,
+    ) -> Result<(), DataError> {
+        let chunks = chunker.chunk(source_path, language, text);
+        let Some(batch) = chunks_to_batch(&chunks)? else {
+            return Ok(());
+        };
+
+        self.append_batches(table, vec![batch]).await
+    }
+}
+
+/// Build the `id/text/tokens/source_path/start_byte/end_byte` batch `append_batches` expects,
+/// from a chunker's output. Returns `None` for an empty chunk list (nothing to ingest).
+fn chunks_to_batch(chunks: &[goni_chunker::Chunk]) -> Result<Option<ArrowBatchHandle>, DataError> {
+    if chunks.is_empty() {
+        return Ok(None);
+    }
+
+    let mut id_builder = StringBuilder::new();
+    let mut text_builder = StringBuilder::new();
+    let mut tokens: Vec<u32> = Vec::with_capacity(chunks.len());
+    let mut source_path_builder = StringBuilder::new();
+    let mut start_bytes: Vec<u32> = Vec::with_capacity(chunks.len());
+    let mut end_bytes: Vec<u32> = Vec::with_capacity(chunks.len());
+
+    for chunk in chunks {
+        id_builder.append_value(format!("{}#{}-{}", chunk.source_path, chunk.start_byte, chunk.end_byte));
+        text_builder.append_value(&chunk.text);
+        tokens.push(chunk.token_count as u32);
+        source_path_builder.append_value(&chunk.source_path);
+        start_bytes.push(chunk.start_byte as u32);
+        end_bytes.push(chunk.end_byte as u32);
+    }
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("text", DataType::Utf8, false),
+        Field::new("tokens", DataType::UInt32, false),
+        Field::new("source_path", DataType::Utf8, true),
+        Field::new("start_byte", DataType::UInt32, true),
+        Field::new("end_byte", DataType::UInt32, true),
+    ]));
+
+    let batch = ArrowBatch::try_new(
+        schema,
+        vec![
+            Arc::new(id_builder.finish()),
+            Arc::new(text_builder.finish()),
+            Arc::new(UInt32Array::from(tokens)),
+            Arc::new(source_path_builder.finish()),
+            Arc::new(UInt32Array::from(start_bytes)),
+            Arc::new(UInt32Array::from(end_bytes)),
+        ],
+    )
+    .map_err(|e| DataError {
+        message: format!("record batch error: {e}"),
+    })?;
+
+    Ok(Some(Arc::new(batch)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goni_chunker::{Chunker, HeuristicChunker, Language};
+
+    #[test]
+    fn chunks_to_batch_returns_none_for_empty_input() {
+        assert!(chunks_to_batch(&[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn ingest_document_chunks_carry_source_path_and_byte_offsets() {
+        let chunker = HeuristicChunker::new(64, 0);
+        let chunks = chunker.chunk("notes.md", Language::Markdown, "# Title\nsome body text\n");
+        let batch = chunks_to_batch(&chunks).unwrap().expect("non-empty document yields a batch");
+
+        assert_eq!(batch.num_rows(), chunks.len());
+        let source_paths = batch
+            .column(batch.schema().index_of("source_path").unwrap())
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let start_bytes = batch
+            .column(batch.schema().index_of("start_byte").unwrap())
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .unwrap();
+        let end_bytes = batch
+            .column(batch.schema().index_of("end_byte").unwrap())
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .unwrap();
+
+        for (row, chunk) in chunks.iter().enumerate() {
+            assert_eq!(source_paths.value(row), "notes.md");
+            assert_eq!(start_bytes.value(row), chunk.start_byte as u32);
+            assert_eq!(end_bytes.value(row), chunk.end_byte as u32);
+        }
+    }
 }
@@ -1,9 +1,16 @@
 use std::{collections::HashMap, sync::Arc};
 
+use arrow_array::{
+    builder::StringBuilder, Array, ArrayRef, FixedSizeBinaryArray, FixedSizeListArray,
+    Float32Array, LargeStringArray, UInt32Array,
+};
+use arrow_schema::{DataType, Field, Schema};
 use async_trait::async_trait;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::SessionContext;
 use tokio::sync::Mutex;
 
-use crate::{ArrowBatchHandle, DataError, DataPlane};
+use crate::{ArrowBatch, ArrowBatchHandle, DataError, DataPlane, RagFilter};
 
 /// In-memory Arrow "spine" data plane.
 ///
@@ -11,11 +18,26 @@ use crate::{ArrowBatchHandle, DataError, DataPlane};
 /// during early kernel bring-up. This makes specs like AuditRecords/StateSnapshots actionable
 /// without committing to DuckDB/LanceDB yet.
 ///
-/// NOTE: This is not durable and does not implement SQL queries.
+/// Queries run through a fresh DataFusion `SessionContext` per call: every table appended via
+/// `append_batches` is registered as a `MemTable` (one partition holding all appended batches),
+/// so joins/filters/aggregates across e.g. Requests/Tasks/LlmCalls work without a separate
+/// storage engine. NOTE: this is not durable — table contents live only in process memory.
 pub struct InMemorySpineDataPlane {
     tables: Mutex<HashMap<String, Vec<ArrowBatchHandle>>>,
 }
 
+/// A row pulled out of the `Embeddings` table: the chunk it points at, plus the vector.
+struct EmbeddingRow {
+    chunk_id: String,
+    vector: Vec<f32>,
+}
+
+/// A row pulled out of the `Chunks` table: text + token count, keyed by chunk_id.
+struct ChunkRow {
+    text: String,
+    token_count: u32,
+}
+
 impl InMemorySpineDataPlane {
     pub fn new() -> Self {
         Self {
@@ -28,14 +50,297 @@ impl InMemorySpineDataPlane {
         let inner = self.tables.lock().await;
         inner.get(table).cloned().unwrap_or_default()
     }
+
+    /// Pull every row out of the `Embeddings` table across all appended batches.
+    async fn all_embeddings(&self) -> Result<Vec<EmbeddingRow>, DataError> {
+        let batches = self.get_table("Embeddings").await;
+        let mut rows = Vec::new();
+        for batch in &batches {
+            let schema = batch.schema();
+            let id_idx = schema.index_of("chunk_id").map_err(|_| DataError {
+                message: "Embeddings missing chunk_id column".into(),
+            })?;
+            let vec_idx = schema.index_of("vector").map_err(|_| DataError {
+                message: "Embeddings missing vector column".into(),
+            })?;
+
+            let ids = batch
+                .column(id_idx)
+                .as_any()
+                .downcast_ref::<FixedSizeBinaryArray>()
+                .ok_or_else(|| DataError {
+                    message: "Embeddings.chunk_id is not FixedSizeBinary".into(),
+                })?;
+            let vectors = batch
+                .column(vec_idx)
+                .as_any()
+                .downcast_ref::<FixedSizeListArray>()
+                .ok_or_else(|| DataError {
+                    message: "Embeddings.vector is not FixedSizeList".into(),
+                })?;
+            let dim = vectors.value_length() as usize;
+            let values = vectors
+                .values()
+                .as_any()
+                .downcast_ref::<Float32Array>()
+                .ok_or_else(|| DataError {
+                    message: "Embeddings.vector values are not Float32".into(),
+                })?;
+            let raw: &[f32] = values.values();
+
+            for row in 0..batch.num_rows() {
+                if ids.is_null(row) || vectors.is_null(row) {
+                    continue;
+                }
+                let start = row * dim;
+                rows.push(EmbeddingRow {
+                    chunk_id: hex::encode(ids.value(row)),
+                    vector: raw[start..start + dim].to_vec(),
+                });
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Pull every row out of the `Chunks` table, keyed by hex-encoded chunk_id.
+    async fn all_chunks(&self) -> Result<HashMap<String, ChunkRow>, DataError> {
+        let batches = self.get_table("Chunks").await;
+        let mut out = HashMap::new();
+        for batch in &batches {
+            let schema = batch.schema();
+            let id_idx = schema.index_of("chunk_id").map_err(|_| DataError {
+                message: "Chunks missing chunk_id column".into(),
+            })?;
+            let text_idx = schema.index_of("text").map_err(|_| DataError {
+                message: "Chunks missing text column".into(),
+            })?;
+            let tokens_idx = schema.index_of("token_count").map_err(|_| DataError {
+                message: "Chunks missing token_count column".into(),
+            })?;
+
+            let ids = batch
+                .column(id_idx)
+                .as_any()
+                .downcast_ref::<FixedSizeBinaryArray>()
+                .ok_or_else(|| DataError {
+                    message: "Chunks.chunk_id is not FixedSizeBinary".into(),
+                })?;
+            let texts = batch
+                .column(text_idx)
+                .as_any()
+                .downcast_ref::<LargeStringArray>()
+                .ok_or_else(|| DataError {
+                    message: "Chunks.text is not LargeUtf8".into(),
+                })?;
+            let token_counts = batch
+                .column(tokens_idx)
+                .as_any()
+                .downcast_ref::<UInt32Array>()
+                .ok_or_else(|| DataError {
+                    message: "Chunks.token_count is not UInt32".into(),
+                })?;
+
+            for row in 0..batch.num_rows() {
+                if ids.is_null(row) {
+                    continue;
+                }
+                out.insert(
+                    hex::encode(ids.value(row)),
+                    ChunkRow {
+                        text: if texts.is_null(row) { String::new() } else { texts.value(row).to_string() },
+                        token_count: if token_counts.is_null(row) { 0 } else { token_counts.value(row) },
+                    },
+                );
+            }
+        }
+        Ok(out)
+    }
+
+    fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+        let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+        let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm_a == 0.0 || norm_b == 0.0 {
+            0.0
+        } else {
+            dot / (norm_a * norm_b)
+        }
+    }
+
+    /// Rank candidate chunk_ids by cosine similarity against `query_embedding`, descending.
+    async fn dense_ranking(&self, query_embedding: &[f32]) -> Result<Vec<String>, DataError> {
+        let mut embeddings = self.all_embeddings().await?;
+        embeddings.retain(|row| row.vector.len() == query_embedding.len());
+        let mut scored: Vec<(String, f32)> = embeddings
+            .into_iter()
+            .map(|row| {
+                let score = Self::cosine_similarity(query_embedding, &row.vector);
+                (row.chunk_id, score)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(scored.into_iter().map(|(id, _)| id).collect())
+    }
+
+    /// Rank candidate chunk_ids by a BM25-lite keyword score over `Chunks.text`, descending.
+    fn keyword_ranking(chunks: &HashMap<String, ChunkRow>, query_text: &str) -> Vec<String> {
+        const K1: f32 = 1.2;
+        const B: f32 = 0.75;
+
+        let query_terms: Vec<String> = query_text
+            .split_whitespace()
+            .map(|t| t.to_lowercase())
+            .collect();
+        if query_terms.is_empty() || chunks.is_empty() {
+            return Vec::new();
+        }
+
+        let doc_terms: HashMap<&String, Vec<String>> = chunks
+            .iter()
+            .map(|(id, row)| (id, row.text.split_whitespace().map(|t| t.to_lowercase()).collect()))
+            .collect();
+
+        let n = doc_terms.len() as f32;
+        let avg_len: f32 =
+            doc_terms.values().map(|t| t.len() as f32).sum::<f32>() / n.max(1.0);
+
+        let mut df: HashMap<&str, u32> = HashMap::new();
+        for qt in &query_terms {
+            let count = doc_terms
+                .values()
+                .filter(|terms| terms.iter().any(|t| t == qt))
+                .count() as u32;
+            df.insert(qt.as_str(), count);
+        }
+
+        let mut scored: Vec<(String, f32)> = doc_terms
+            .iter()
+            .map(|(id, terms)| {
+                let doc_len = terms.len() as f32;
+                let score: f32 = query_terms
+                    .iter()
+                    .map(|qt| {
+                        let freq = terms.iter().filter(|t| *t == qt).count() as f32;
+                        if freq == 0.0 {
+                            return 0.0;
+                        }
+                        let n_q = *df.get(qt.as_str()).unwrap_or(&0) as f32;
+                        let idf = ((n - n_q + 0.5) / (n_q + 0.5) + 1.0).ln();
+                        idf * (freq * (K1 + 1.0))
+                            / (freq + K1 * (1.0 - B + B * doc_len / avg_len))
+                    })
+                    .sum();
+                ((*id).clone(), score)
+            })
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Materialize the top_k chunk_ids (in the given order) into the common RAG output schema:
+    /// id/text/tokens/embedding, mirroring `QdrantDataPlane::rag_candidates`.
+    fn build_candidate_batch(
+        ranked_ids: &[String],
+        chunks: &HashMap<String, ChunkRow>,
+        embeddings_by_id: &HashMap<String, Vec<f32>>,
+        top_k: usize,
+    ) -> Result<ArrowBatchHandle, DataError> {
+        let dim = embeddings_by_id.values().next().map(|v| v.len()).unwrap_or(0);
+        if dim == 0 {
+            return Err(DataError {
+                message: "no embeddings available to build candidate batch".into(),
+            });
+        }
+
+        let mut id_builder = StringBuilder::new();
+        let mut text_builder = StringBuilder::new();
+        let mut tokens: Vec<u32> = Vec::new();
+        let mut embedding_vals: Vec<f32> = Vec::new();
+
+        for id in ranked_ids.iter().take(top_k) {
+            let Some(vector) = embeddings_by_id.get(id) else { continue };
+            if vector.len() != dim {
+                continue;
+            }
+            let chunk = chunks.get(id);
+            id_builder.append_value(id);
+            text_builder.append_value(chunk.map(|c| c.text.as_str()).unwrap_or(""));
+            tokens.push(chunk.map(|c| c.token_count).unwrap_or(0));
+            embedding_vals.extend_from_slice(vector);
+        }
+
+        let id_array = id_builder.finish();
+        let text_array = text_builder.finish();
+        let token_array = UInt32Array::from(tokens);
+        let value_array = Float32Array::from(embedding_vals);
+
+        let item_field = Arc::new(Field::new("item", DataType::Float32, false));
+        let embedding_array = FixedSizeListArray::try_new(
+            item_field.clone(),
+            dim as i32,
+            Arc::new(value_array) as ArrayRef,
+            None,
+        )
+        .map_err(|e| DataError {
+            message: format!("embedding array error: {e}"),
+        })?;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("text", DataType::Utf8, false),
+            Field::new("tokens", DataType::UInt32, false),
+            Field::new("embedding", DataType::FixedSizeList(item_field, dim as i32), false),
+        ]));
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(id_array),
+            Arc::new(text_array),
+            Arc::new(token_array),
+            Arc::new(embedding_array),
+        ];
+
+        let batch = ArrowBatch::try_new(schema, columns).map_err(|e| DataError {
+            message: format!("record batch error: {e}"),
+        })?;
+
+        Ok(Arc::new(batch))
+    }
+
 }
 
 #[async_trait]
 impl DataPlane for InMemorySpineDataPlane {
-    async fn query(&self, _sql: &str) -> Result<Vec<ArrowBatchHandle>, DataError> {
-        Err(DataError {
-            message: "InMemorySpineDataPlane does not support SQL queries".into(),
-        })
+    /// Register every appended table as a DataFusion `MemTable` and run `sql` through a fresh
+    /// `SessionContext`, returning the result batches.
+    async fn query(&self, sql: &str) -> Result<Vec<ArrowBatchHandle>, DataError> {
+        let tables = self.tables.lock().await;
+
+        let ctx = SessionContext::new();
+        for (name, batches) in tables.iter() {
+            if batches.is_empty() {
+                continue;
+            }
+            let schema = batches[0].schema();
+            let partition: Vec<ArrowBatch> = batches.iter().map(|b| (**b).clone()).collect();
+            let mem_table = MemTable::try_new(schema, vec![partition]).map_err(|e| DataError {
+                message: format!("failed to register table '{name}': {e}"),
+            })?;
+            ctx.register_table(name.as_str(), Arc::new(mem_table))
+                .map_err(|e| DataError {
+                    message: format!("failed to register table '{name}': {e}"),
+                })?;
+        }
+        drop(tables);
+
+        let df = ctx.sql(sql).await.map_err(|e| DataError {
+            message: format!("sql planning error: {e}"),
+        })?;
+        let results = df.collect().await.map_err(|e| DataError {
+            message: format!("sql execution error: {e}"),
+        })?;
+
+        Ok(results.into_iter().map(Arc::new).collect())
     }
 
     async fn append_batches(
@@ -51,14 +356,109 @@ impl DataPlane for InMemorySpineDataPlane {
         Ok(())
     }
 
+    /// Pure vector search: brute-force cosine similarity against the appended `Embeddings`
+    /// batches, returning the top_k `chunk_id`s (joined with `Chunks` for text/tokens).
     async fn rag_candidates(
         &self,
         _collection: &str,
-        _query_embedding: &[f32],
-        _top_k: usize,
+        query_embedding: &[f32],
+        top_k: usize,
+        filter: Option<&RagFilter>,
     ) -> Result<ArrowBatchHandle, DataError> {
-        Err(DataError {
-            message: "InMemorySpineDataPlane has no RAG".into(),
-        })
+        // The Chunks/Embeddings tables don't carry arbitrary payload columns, so there is
+        // nothing to filter on: fail closed rather than silently ignoring the scope.
+        if filter.is_some_and(|f| !f.is_empty()) {
+            return Err(DataError {
+                message: "InMemorySpineDataPlane does not support rag_candidates filters".into(),
+            });
+        }
+
+        let embeddings = self.all_embeddings().await?;
+        let chunks = self.all_chunks().await?;
+        let embeddings_by_id: HashMap<String, Vec<f32>> = embeddings
+            .into_iter()
+            .map(|row| (row.chunk_id, row.vector))
+            .collect();
+
+        let ranked_ids = self.dense_ranking(query_embedding).await?;
+        Self::build_candidate_batch(&ranked_ids, &chunks, &embeddings_by_id, top_k)
+    }
+
+    /// BM25-lite keyword search over `Chunks.text` (see `keyword_ranking`), in the same
+    /// `id/text/tokens/embedding` schema as `rag_candidates` so the two can be fused with RRF.
+    async fn keyword_candidates(
+        &self,
+        _collection: &str,
+        query_text: &str,
+        top_k: usize,
+        filter: Option<&RagFilter>,
+    ) -> Result<ArrowBatchHandle, DataError> {
+        if filter.is_some_and(|f| !f.is_empty()) {
+            return Err(DataError {
+                message: "InMemorySpineDataPlane does not support keyword_candidates filters".into(),
+            });
+        }
+
+        let embeddings = self.all_embeddings().await?;
+        let chunks = self.all_chunks().await?;
+        let embeddings_by_id: HashMap<String, Vec<f32>> = embeddings
+            .into_iter()
+            .map(|row| (row.chunk_id, row.vector))
+            .collect();
+
+        let ranked_ids = Self::keyword_ranking(&chunks, query_text);
+        Self::build_candidate_batch(&ranked_ids, &chunks, &embeddings_by_id, top_k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChunkRow, InMemorySpineDataPlane};
+    use std::collections::HashMap;
+
+    #[test]
+    fn cosine_similarity_of_identical_vectors_is_one() {
+        let a = [1.0, 2.0, 3.0];
+        assert!((InMemorySpineDataPlane::cosine_similarity(&a, &a) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cosine_similarity_of_orthogonal_vectors_is_zero() {
+        let a = [1.0, 0.0];
+        let b = [0.0, 1.0];
+        assert!(InMemorySpineDataPlane::cosine_similarity(&a, &b).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cosine_similarity_guards_against_zero_vectors() {
+        let zero = [0.0, 0.0];
+        let other = [1.0, 1.0];
+        assert_eq!(InMemorySpineDataPlane::cosine_similarity(&zero, &other), 0.0);
+    }
+
+    fn chunk(text: &str) -> ChunkRow {
+        ChunkRow {
+            text: text.to_string(),
+            token_count: text.split_whitespace().count() as u32,
+        }
+    }
+
+    #[test]
+    fn keyword_ranking_favors_documents_matching_more_query_terms() {
+        let mut chunks = HashMap::new();
+        chunks.insert("a".to_string(), chunk("the quick brown fox"));
+        chunks.insert("b".to_string(), chunk("a slow turtle naps"));
+
+        let ranked = InMemorySpineDataPlane::keyword_ranking(&chunks, "quick fox");
+        assert_eq!(ranked.first().map(String::as_str), Some("a"));
+        assert!(!ranked.contains(&"b".to_string()));
+    }
+
+    #[test]
+    fn keyword_ranking_returns_empty_for_blank_query_or_no_chunks() {
+        let mut chunks = HashMap::new();
+        chunks.insert("a".to_string(), chunk("some text"));
+        assert!(InMemorySpineDataPlane::keyword_ranking(&chunks, "   ").is_empty());
+        assert!(InMemorySpineDataPlane::keyword_ranking(&HashMap::new(), "text").is_empty());
     }
 }
